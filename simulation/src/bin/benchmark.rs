@@ -0,0 +1,200 @@
+use std::thread;
+
+use clap::Parser;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use simulation::{run_game, RunOptions, Strategies};
+use types::{GameConfig, GameState, Strategy};
+
+/// Plays every pair of strategies against each other and prints how often each one wins
+#[derive(Parser, Debug)]
+struct Params {
+    /// Strategies to compare, by registry name
+    #[arg(short, long, value_delimiter = ',', default_values_t = ["default".to_string(), "random".to_string()])]
+    strategies: Vec<String>,
+    /// Games played for each pair of strategies
+    #[arg(short, long, default_value_t = 100)]
+    games: usize,
+    /// Players at the table, split evenly between the two strategies, with any extra seat
+    /// alternating between them
+    #[arg(short, long, default_value_t = 4)]
+    players: usize,
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+}
+
+/// Plays `num_games` between `first` and `second` and returns the number of games each side
+/// won. Every game is a fresh, shuffled table, so nobody keeps the same neighbours, and with an
+/// odd number of players the extra seat goes to each side in turn.
+fn play_matchup(
+    first: &str,
+    second: &str,
+    num_players: usize,
+    num_games: usize,
+    seed: u64,
+) -> (usize, usize) {
+    let mut seed_rng = StdRng::seed_from_u64(seed);
+    let mut first_wins = 0;
+    for game_idx in 0..num_games {
+        let mut first_names = Vec::new();
+        let player_inputs: Vec<(String, Box<dyn Strategy>)> = (0..num_players)
+            .map(|idx| {
+                let is_first = (idx + game_idx) % 2 == 0;
+                let strategy_name = if is_first { first } else { second };
+                let strategy = strategy_name
+                    .parse::<Strategies>()
+                    .expect("Unable to parse strategy")
+                    .seeded(seed_rng.gen());
+                let name = format!("{strategy_name} #{idx}");
+                if is_first {
+                    first_names.push(name.clone());
+                }
+                (name, strategy.into())
+            })
+            .collect();
+        let mut game_state =
+            GameState::new_with_seed(player_inputs, GameConfig::default(), seed_rng.gen())
+                .expect("Unable to set up game");
+        // ids are assigned by the game, so pick out `first`'s by their exact names
+        let first_ids: Vec<_> = game_state
+            .table
+            .iter()
+            .filter(|player| first_names.contains(&player.state.name))
+            .map(|player| player.state.id)
+            .collect();
+
+        let standings = run_game(&mut game_state, RunOptions::default(), None);
+        let (winner, _, _) = standings.first().expect("Someone always wins");
+        if first_ids.contains(winner) {
+            first_wins += 1;
+        }
+    }
+    (first_wins, num_games - first_wins)
+}
+
+/// Win rate, as a percentage, of each strategy (row) against each other one (column), with
+/// `None` on the diagonal
+fn win_rates(args: &Params) -> Vec<Vec<Option<f64>>> {
+    let num_strategies = args.strategies.len();
+    let mut seed_rng = StdRng::seed_from_u64(args.seed);
+    let matchups: Vec<(usize, usize, u64)> = (0..num_strategies)
+        .flat_map(|a| (a + 1..num_strategies).map(move |b| (a, b)))
+        .map(|(a, b)| (a, b, seed_rng.gen()))
+        .collect();
+
+    // each matchup is an independent seeded session, so they can run side by side
+    let results: Vec<(usize, usize, (usize, usize))> = thread::scope(|scope| {
+        let handles: Vec<_> = matchups
+            .iter()
+            .map(|&(a, b, seed)| {
+                let (first, second) = (&args.strategies[a], &args.strategies[b]);
+                scope.spawn(move || {
+                    (
+                        a,
+                        b,
+                        play_matchup(first, second, args.players, args.games, seed),
+                    )
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("Matchup thread panicked"))
+            .collect()
+    });
+
+    let mut win_rates = vec![vec![None; num_strategies]; num_strategies];
+    for (a, b, (a_wins, b_wins)) in results {
+        let num_games = args.games.max(1) as f64;
+        win_rates[a][b] = Some(100.0 * a_wins as f64 / num_games);
+        win_rates[b][a] = Some(100.0 * b_wins as f64 / num_games);
+    }
+    win_rates
+}
+
+fn format_win_rates(args: &Params, win_rates: Vec<Vec<Option<f64>>>) -> String {
+    let width = args
+        .strategies
+        .iter()
+        .map(String::len)
+        .max()
+        .unwrap_or_default()
+        .max(7);
+    let mut output = format!(
+        "Win rate of row vs column over {} games each:\n",
+        args.games
+    );
+    output += &format!("{:width$}", "");
+    for name in &args.strategies {
+        output += &format!(" {name:>width$}");
+    }
+    output += "\n";
+    for (name, row) in args.strategies.iter().zip(win_rates) {
+        output += &format!("{name:width$}");
+        for rate in row {
+            match rate {
+                Some(rate) => output += &format!(" {:>width$}", format!("{rate:.1}%")),
+                None => output += &format!(" {:>width$}", "-"),
+            }
+        }
+        output += "\n";
+    }
+    output
+}
+
+fn main() {
+    env_logger::init();
+    let args = Params::parse();
+    log::info!("args: {args:?}");
+    assert!(
+        args.players >= 2,
+        "Need at least 2 players, got {}",
+        args.players
+    );
+    for name in &args.strategies {
+        if let Err(err) = name.parse::<Strategies>() {
+            panic!("{err}");
+        }
+    }
+
+    let win_rates = win_rates(&args);
+    print!("{}", format_win_rates(&args, win_rates));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wins_are_credited_to_the_right_side() {
+        // passing never wins, so every game goes to whichever side isn't passing, with either
+        // side holding the extra seat
+        for num_players in [3, 4] {
+            assert_eq!(play_matchup("default", "pass", num_players, 6, 0), (6, 0));
+            assert_eq!(play_matchup("pass", "default", num_players, 6, 0), (0, 6));
+        }
+    }
+
+    #[test]
+    fn prints_each_win_rate() {
+        let args = Params {
+            strategies: vec![
+                "default".to_string(),
+                "random".to_string(),
+                "pass".to_string(),
+            ],
+            games: 4,
+            players: 3,
+            seed: 0,
+        };
+        let output = format_win_rates(&args, win_rates(&args));
+        assert_eq!(output, format_win_rates(&args, win_rates(&args)));
+        assert!(output.contains("over 4 games each"));
+        let rows: Vec<_> = output.lines().skip(2).collect();
+        assert_eq!(rows.len(), 3);
+        for (name, row) in args.strategies.iter().zip(rows) {
+            assert!(row.starts_with(name.as_str()));
+            assert_eq!(row.matches('%').count(), 2);
+        }
+        assert!(output.contains("0.0%") && output.contains("100.0%"));
+    }
+}
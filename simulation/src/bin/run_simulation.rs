@@ -1,10 +1,14 @@
-use std::{path::PathBuf, str::FromStr};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
 
 use clap::Parser;
+use rand::{rngs::StdRng, Rng, SeedableRng};
 use serde::Deserialize;
-use simulation::run_game;
-use strategies::{DefaultStrategy, InputStrategy, RandomStrategy};
-use types::{GameState, Strategy};
+use simulation::{run_game, score_game, RunOptions, ScoringConfig, Strategies};
+use types::{GameConfig, GameState, Strategy};
+use uuid::Uuid;
 
 #[derive(Parser, Debug)]
 struct Params {
@@ -12,74 +16,271 @@ struct Params {
     config: PathBuf,
     #[arg(short, long)]
     delay_ms: Option<u64>,
+    /// Play this many games, print a summary and exit, instead of playing forever
+    #[arg(short, long)]
+    games: Option<usize>,
+    /// Seed for the deal, seating and any random strategies, for reproducible runs
+    #[arg(short, long)]
+    seed: Option<u64>,
+    /// Cards swapped between the Asshole and the President before each game, overriding the
+    /// config file's rules [default: 2]
+    #[arg(long)]
+    president_swap: Option<usize>,
+    /// Cards swapped between the ViceAsshole and the VicePresident before each game, overriding
+    /// the config file's rules [default: 1]
+    #[arg(long)]
+    vp_swap: Option<usize>,
+    /// Cards swapped between the ViceAsshole and the Secretary before each game, overriding the
+    /// config file's rules [default: 0]
+    #[arg(long)]
+    secretary_swap: Option<usize>,
+    /// Pass for players who can't play instead of asking their strategy
+    #[arg(long)]
+    auto_pass: bool,
+    /// Log each turn's permitted actions at info level
+    #[arg(long)]
+    log_actions: bool,
+    /// Log more: -v for info, -vv for debug, -vvv for trace. RUST_LOG still takes precedence
+    #[arg(short, long, action = clap::ArgAction::Count, conflicts_with = "quiet")]
+    verbose: u8,
+    /// Log nothing, not even errors
+    #[arg(short, long)]
+    quiet: bool,
+}
+
+impl Params {
+    /// The config file's rules with any rule flags given on the command line applied on top
+    fn game_config(&self, rules: GameConfig) -> GameConfig {
+        GameConfig {
+            president_swap: self.president_swap.unwrap_or(rules.president_swap),
+            vp_swap: self.vp_swap.unwrap_or(rules.vp_swap),
+            secretary_swap: self.secretary_swap.unwrap_or(rules.secretary_swap),
+            ..rules
+        }
+    }
+
+    fn run_options(&self) -> RunOptions {
+        RunOptions {
+            delay_ms: self.delay_ms,
+            auto_pass: self.auto_pass,
+            log_permitted_actions: self.log_actions,
+        }
+    }
+
+    fn log_level(&self) -> log::LevelFilter {
+        if self.quiet {
+            return log::LevelFilter::Off;
+        }
+        match self.verbose {
+            0 => log::LevelFilter::Error,
+            1 => log::LevelFilter::Info,
+            2 => log::LevelFilter::Debug,
+            _ => log::LevelFilter::Trace,
+        }
+    }
 }
 
 #[derive(Deserialize)]
 struct PlayerConfig {
     name: String,
     strategy: String,
+    params: Option<serde_json::Value>,
 }
 
 #[derive(Deserialize)]
 struct PlayersConfig {
     players: Vec<PlayerConfig>,
+    /// Game rules, with any left out taking their default
+    #[serde(default)]
+    rules: GameConfig,
 }
 
-#[derive(Debug)]
-pub enum Strategies {
-    Default(DefaultStrategy),
-    Random(RandomStrategy),
-    Input(InputStrategy),
-}
-
-impl FromStr for Strategies {
-    type Err = String; // TODO
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s.to_lowercase().as_str() {
-            "default" => Ok(Strategies::Default(DefaultStrategy::default())),
-            "random" => Ok(Strategies::Random(RandomStrategy::default())),
-            "input" => Ok(Strategies::Input(InputStrategy::default())),
-            _ => Err(format!("Unable to parse {s:?} to Strategy impl")),
-        }
-    }
+fn get_config(path: &Path) -> PlayersConfig {
+    let f = std::fs::File::open(path).expect("File to open");
+    serde_yaml::from_reader(f).expect("File to parse to PlayersConfig")
 }
 
-impl From<Strategies> for Box<dyn Strategy> {
-    fn from(value: Strategies) -> Self {
-        match value {
-            Strategies::Default(strat) => Box::new(strat) as Box<dyn Strategy>,
-            Strategies::Random(strat) => Box::new(strat) as Box<dyn Strategy>,
-            Strategies::Input(strat) => Box::new(strat) as Box<dyn Strategy>,
-        }
+fn print_summary(
+    game_state: &GameState,
+    places: &HashMap<Uuid, Vec<usize>>,
+    points: &HashMap<Uuid, i32>,
+) {
+    let mut rows: Vec<_> = game_state
+        .table
+        .iter()
+        .map(|player| {
+            let player_places = places
+                .get(&player.state.id)
+                .map(Vec::as_slice)
+                .unwrap_or_default();
+            let wins = player_places.iter().filter(|&&place| place == 1).count();
+            let avg_place =
+                player_places.iter().sum::<usize>() as f64 / player_places.len().max(1) as f64;
+            let player_points = points.get(&player.state.id).copied().unwrap_or(0);
+            (player.state.name.as_str(), player_points, wins, avg_place)
+        })
+        .collect();
+    rows.sort_by(|a, b| b.1.cmp(&a.1).then(a.3.total_cmp(&b.3)));
+    for (name, player_points, wins, avg_place) in rows {
+        println!("{name}: {player_points} points, {wins} wins, average place {avg_place:.2}");
     }
 }
 
-fn get_config(path: PathBuf) -> PlayersConfig {
-    let f = std::fs::File::open(path).expect("File to open");
-    serde_yaml::from_reader(f).expect("File to parse to PlayersConfig")
-}
-
 fn main() {
-    env_logger::init();
     let args = Params::parse();
+    env_logger::Builder::new()
+        .filter_level(args.log_level())
+        .parse_default_env()
+        .init();
     log::info!("args: {args:?}");
-    let config = get_config(args.config);
+    let config = get_config(&args.config);
+    // every seeded component gets its own seed derived from the top-level one
+    let mut seed_rng = args.seed.map(StdRng::seed_from_u64);
     let player_inputs: Vec<(String, Box<dyn Strategy>)> = config
         .players
         .into_iter()
         .map(|player_conf| {
-            (
-                player_conf.name,
-                (&player_conf.strategy)
-                    .parse::<Strategies>()
-                    .expect("Unable to parse strategy")
-                    .into(),
-            )
+            let strategy = player_conf
+                .strategy
+                .parse::<Strategies>()
+                .expect("Unable to parse strategy");
+            let strategy = match seed_rng.as_mut() {
+                Some(rng) => strategy.seeded(rng.gen()),
+                None => strategy,
+            };
+            let strategy = match &player_conf.params {
+                Some(params) => strategy
+                    .with_params(params)
+                    .expect("Unable to apply strategy params"),
+                None => strategy,
+            };
+            (player_conf.name, strategy.into())
         })
         .collect();
-    let mut game_state = GameState::new(player_inputs);
-    loop {
-        run_game(&mut game_state, args.delay_ms);
+    let game_config = args.game_config(config.rules);
+    let game_state = match seed_rng.as_mut() {
+        Some(rng) => GameState::new_with_seed(player_inputs, game_config, rng.gen()),
+        None => GameState::new_with_config(player_inputs, game_config),
+    };
+    let mut game_state = game_state.unwrap_or_else(|err| panic!("Unable to set up game: {err}"));
+    match args.games {
+        Some(num_games) => {
+            let scoring = ScoringConfig::linear(game_state.table.len());
+            let mut places: HashMap<Uuid, Vec<usize>> = HashMap::new();
+            let mut points: HashMap<Uuid, i32> = HashMap::new();
+            for _ in 0..num_games {
+                let standings = run_game(&mut game_state, args.run_options(), None);
+                for (p_id, game_points) in score_game(&standings, &scoring) {
+                    *points.entry(p_id).or_default() += game_points;
+                }
+                for (p_id, place, _) in standings {
+                    places.entry(p_id).or_default().push(place);
+                }
+            }
+            println!("Standings after {num_games} games:");
+            print_summary(&game_state, &places, &points);
+        }
+        None => loop {
+            run_game(&mut game_state, args.run_options(), None);
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn swap_flags_override_the_config_rules() {
+        let rules = GameConfig {
+            president_swap: 3,
+            vp_swap: 2,
+            ..GameConfig::default()
+        };
+        let args = Params::parse_from(["run_simulation", "--president-swap", "1"]);
+        let game_config = args.game_config(rules);
+        assert_eq!(game_config.president_swap, 1);
+        assert_eq!(game_config.vp_swap, 2);
+        assert_eq!(game_config.secretary_swap, 0);
+    }
+
+    #[test]
+    fn driver_flags_set_the_run_options() {
+        let args = Params::parse_from(["run_simulation", "--log-actions", "-d", "5"]);
+        let options = args.run_options();
+        assert!(options.log_permitted_actions);
+        assert!(!options.auto_pass);
+        assert_eq!(options.delay_ms, Some(5));
+        let args = Params::parse_from(["run_simulation", "--auto-pass"]);
+        let options = args.run_options();
+        assert!(options.auto_pass);
+        assert!(!options.log_permitted_actions);
+        assert_eq!(options.delay_ms, None);
+    }
+
+    #[test]
+    fn verbosity_flags_set_the_log_level() {
+        let level =
+            |args: &[&str]| Params::parse_from([&["run_simulation"], args].concat()).log_level();
+        assert_eq!(level(&[]), log::LevelFilter::Error);
+        assert_eq!(level(&["-v"]), log::LevelFilter::Info);
+        assert_eq!(level(&["-vv"]), log::LevelFilter::Debug);
+        assert_eq!(level(&["-v", "-v", "-v"]), log::LevelFilter::Trace);
+        assert_eq!(level(&["-q"]), log::LevelFilter::Off);
+        assert!(Params::try_parse_from(["run_simulation", "-q", "-v"]).is_err());
+    }
+
+    #[test]
+    fn parses_per_player_params() {
+        let config: PlayersConfig = serde_yaml::from_str(
+            "players:
+  - name: Alice
+    strategy: random
+    params:
+      seed: 7
+  - name: Bob
+    strategy: default
+",
+        )
+        .unwrap();
+        assert_eq!(config.players.len(), 2);
+        assert_eq!(
+            config.players[0].params,
+            Some(serde_json::json!({"seed": 7}))
+        );
+        assert_eq!(config.players[1].params, None);
+    }
+
+    #[test]
+    fn rules_section_sets_up_the_game() {
+        let config: PlayersConfig = serde_yaml::from_str(
+            "players:
+  - name: Alice
+    strategy: default
+  - name: Bob
+    strategy: default
+rules:
+  president_swap: 1
+  quads_reverse: true
+",
+        )
+        .unwrap();
+        let args = Params::parse_from(["run_simulation"]);
+        let player_inputs = config
+            .players
+            .into_iter()
+            .map(|player_conf| {
+                let strategy = player_conf.strategy.parse::<Strategies>().unwrap();
+                (player_conf.name, strategy.into())
+            })
+            .collect();
+        let game_state =
+            GameState::new_with_config(player_inputs, args.game_config(config.rules)).unwrap();
+        assert_eq!(game_state.config.president_swap, 1);
+        assert!(game_state.config.quads_reverse);
+        assert_eq!(game_state.config.vp_swap, GameConfig::default().vp_swap);
+
+        let config: PlayersConfig = serde_yaml::from_str("players: []").unwrap();
+        assert_eq!(config.rules, GameConfig::default());
     }
 }
@@ -0,0 +1,145 @@
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use strategies::RandomStrategy;
+use types::{Card, DealMode, GameConfig, GameState, LastPlaceRule, StartingRule, Strategy};
+
+/// Plays `count` short sessions of random players under random rule combinations, checking
+/// after every action that it was permitted, the pile shows what was just played, and no cards
+/// went missing. Session `idx` is built only from `seed + idx`, so a failure names the seed that
+/// reproduces it with `fuzz_games(1, seed)`.
+pub fn fuzz_games(count: usize, seed: u64) {
+    for idx in 0..count {
+        let session_seed = seed.wrapping_add(idx as u64);
+        if let Err(err) = fuzz_session(session_seed) {
+            panic!("Fuzzed session with seed {session_seed} failed: {err}");
+        }
+    }
+}
+
+fn random_config(rng: &mut StdRng) -> GameConfig {
+    let vp_swap = rng.gen_range(0..=1);
+    GameConfig {
+        president_swap: rng.gen_range(vp_swap..=2),
+        vp_swap,
+        secretary_swap: rng.gen_range(0..=vp_swap),
+        twos_clear_pile: rng.gen(),
+        sticky_passes: rng.gen(),
+        quads_reverse: rng.gen(),
+        double_down_clears: rng.gen(),
+        open_with_single: rng.gen(),
+        allow_breaking_sets: rng.gen(),
+        asshole_keeps_starting_card: rng.gen(),
+        starting_rule: if rng.gen() {
+            StartingRule::LowestCard
+        } else {
+            StartingRule::default()
+        },
+        deal_mode: *[DealMode::Random, DealMode::Balanced]
+            .choose(rng)
+            .expect("Not empty"),
+        last_place_rule: *[
+            LastPlaceRule::Ordered,
+            LastPlaceRule::Shared,
+            LastPlaceRule::SharedAssholes,
+        ]
+        .choose(rng)
+        .expect("Not empty"),
+    }
+}
+
+// two games, so the second one has roles and swaps
+fn fuzz_session(seed: u64) -> Result<(), String> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let config = random_config(&mut rng);
+    let num_players = rng.gen_range(2..=7);
+    let player_inputs: Vec<(String, Box<dyn Strategy>)> = (0..num_players)
+        .map(|idx| {
+            let strategy = RandomStrategy::with_seed(rng.gen());
+            (
+                format!("Player{idx}"),
+                Box::new(strategy) as Box<dyn Strategy>,
+            )
+        })
+        .collect();
+    let mut game_state = GameState::new_with_seed(player_inputs, config.clone(), rng.gen())
+        .map_err(|err| format!("unable to set up game with {config:?}: {err}"))?;
+
+    for _ in 0..2 {
+        game_state.run_pregame();
+        check_cards(&game_state)?;
+        while game_state.still_playing() {
+            let available_actions = game_state.permitted_actions();
+            if available_actions.is_empty() {
+                return Err(format!("no permitted actions\n{}", game_state.transcript()));
+            }
+            let public_info = game_state.public_info();
+            let current_player = game_state.current_player_mut();
+            let action = current_player.strategy.select_action(
+                &current_player.state,
+                &public_info,
+                &available_actions,
+            );
+            if !available_actions.contains(&action) || !game_state.is_action_legal(&action) {
+                return Err(format!("{action} was chosen but isn't permitted"));
+            }
+            game_state
+                .try_apply_action(&action)
+                .map_err(|err| format!("{action} was permitted but failed: {err}"))?;
+            if let Some(last_play) = action.card_plays().last() {
+                if game_state.top_card.is_some_and(|top| top != *last_play) {
+                    return Err(format!(
+                        "{action} was played but the pile shows {:?}\n{}",
+                        game_state.top_card,
+                        game_state.transcript()
+                    ));
+                }
+            }
+            check_cards(&game_state)?;
+        }
+        game_state.start_new_game();
+    }
+    Ok(())
+}
+
+// every card is in a hand, on the history, or undealt, exactly once
+fn check_cards(game_state: &GameState) -> Result<(), String> {
+    let mut cards: Vec<Card> = game_state
+        .table
+        .iter()
+        .flat_map(|player| player.state.current_hand.iter().copied())
+        .chain(
+            game_state
+                .history
+                .iter()
+                .flat_map(|event| event.action.card_plays())
+                .flat_map(|card_play| card_play.to_vec()),
+        )
+        .chain(game_state.deck_remaining().iter().copied())
+        .collect();
+    let num_cards = cards.len();
+    cards.sort_by_key(|card| card.to_string());
+    cards.dedup();
+    if num_cards != Card::all_cards().len() || cards.len() != num_cards {
+        return Err(format!(
+            "expected each of the {} cards once, found {num_cards} cards of which {} are distinct",
+            Card::all_cards().len(),
+            cards.len()
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fuzz_quick() {
+        fuzz_games(10, 0)
+    }
+
+    #[test]
+    #[ignore = "plays thousands of sessions; run with --ignored"]
+    fn fuzz_long() {
+        fuzz_games(2000, 0)
+    }
+}
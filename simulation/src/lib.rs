@@ -1,24 +1,214 @@
+pub mod fuzz;
+pub mod registry;
+pub mod replay;
+pub mod scoring;
+
 use std::{thread::sleep, time::Duration};
 
-use types::GameState;
+use types::{game_state::PublicInfo, Action, Event, GameState, Role};
+use uuid::Uuid;
+
+pub use crate::{
+    fuzz::fuzz_games,
+    registry::Strategies,
+    replay::validate_replay,
+    scoring::{score_game, ScoringConfig},
+};
+
+pub type EventCallback<'a> = &'a mut dyn FnMut(&Event, &PublicInfo);
+
+/// How `run_game` drives a game, as opposed to the rules in `GameConfig`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RunOptions {
+    /// Pause before each turn, e.g. to watch a game being played
+    pub delay_ms: Option<u64>,
+    /// Pass for players who can't play, without asking their strategy
+    pub auto_pass: bool,
+    /// Log each turn's permitted actions at info rather than debug level
+    pub log_permitted_actions: bool,
+}
 
-pub fn run_game(game_state: &mut GameState, delay_ms: Option<u64>) {
+/// Plays one full game and returns its standings as `(player_id, place, role)`. If given,
+/// `on_event` is called after every pregame and ingame action, e.g. to render the game.
+pub fn run_game(
+    game_state: &mut GameState,
+    options: RunOptions,
+    mut on_event: Option<EventCallback>,
+) -> Vec<(Uuid, usize, Role)> {
     assert_eq!(game_state.history.len(), 0);
-    game_state.run_pregame();
+    let pregame_events = game_state.run_pregame();
+    if let Some(callback) = on_event.as_mut() {
+        let public_info = game_state.public_info();
+        for event in &pregame_events {
+            callback(event, &public_info);
+        }
+    }
     while game_state.still_playing() {
         log::debug!("{game_state}");
-        if let Some(ms) = delay_ms {
+        if let Some(ms) = options.delay_ms {
             sleep(Duration::from_millis(ms));
         }
         let available_actions = game_state.permitted_actions();
-        let public_info = game_state.public_info();
-        let current_player = game_state.current_player_mut();
-        let selected_action = current_player.strategy.select_action(
-            &current_player.state,
-            &public_info,
-            &available_actions,
-        );
+        if options.log_permitted_actions {
+            log::info!(
+                "Available actions for {}: {available_actions:?}",
+                game_state.current_player().state.name
+            );
+        }
+        let selected_action = if options.auto_pass && available_actions == [Action::Pass] {
+            Action::Pass
+        } else {
+            let public_info = game_state.public_info();
+            let current_player = game_state.current_player_mut();
+            current_player.strategy.select_action(
+                &current_player.state,
+                &public_info,
+                &available_actions,
+            )
+        };
         game_state.perform_ingame_action(&selected_action);
+        if let Some(callback) = on_event.as_mut() {
+            let event = game_state.history.last().expect("Just performed an action");
+            callback(event, &game_state.public_info());
+        }
     }
+    let standings = game_state.standings();
     game_state.start_new_game();
+    standings
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use deckofcards::Card as DOCCard;
+    use types::{Card, GameConfig, PlayerState, Strategy};
+
+    use super::*;
+
+    fn random_players(seed: u64) -> Vec<(String, Box<dyn types::Strategy>)> {
+        (0..4)
+            .map(|idx| {
+                let strategy = "random"
+                    .parse::<Strategies>()
+                    .expect("Registered strategy")
+                    .seeded(seed + idx);
+                (format!("p{idx}"), strategy.into())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn run_game_plays_one_game_and_deals_the_next() {
+        let mut game_state =
+            GameState::new_with_seed(random_players(0), GameConfig::default(), 0).unwrap();
+        for _ in 0..3 {
+            let standings = run_game(&mut game_state, RunOptions::default(), None);
+            let places: Vec<_> = standings.iter().map(|&(_, place, _)| place).collect();
+            assert_eq!(places, [1, 2, 3, 4]);
+            assert!(game_state.history.is_empty());
+            assert!(game_state
+                .table
+                .iter()
+                .all(|player| player.state.current_hand.len() == 13));
+        }
+    }
+
+    // finishing order by name for each of `num_games` games of a seeded session
+    fn seeded_results(seed: u64, num_games: usize) -> Vec<Vec<String>> {
+        let mut game_state =
+            GameState::new_with_seed(random_players(seed), GameConfig::default(), seed).unwrap();
+        (0..num_games)
+            .map(|_| {
+                run_game(&mut game_state, RunOptions::default(), None)
+                    .into_iter()
+                    .map(|(id, _, _)| game_state.get_player(id).unwrap().state.name.clone())
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn seeded_sessions_are_reproducible() {
+        assert_eq!(seeded_results(7, 5), seeded_results(7, 5));
+        assert_ne!(seeded_results(7, 5), seeded_results(8, 5));
+    }
+
+    #[test]
+    fn on_event_sees_every_pregame_and_ingame_action() {
+        let mut game_state =
+            GameState::new_with_seed(random_players(1), GameConfig::default(), 1).unwrap();
+        run_game(&mut game_state, RunOptions::default(), None);
+
+        let mut events = Vec::new();
+        let mut callback = |event: &Event, public_info: &PublicInfo| {
+            events.push((*event, public_info.history.len()));
+        };
+        run_game(&mut game_state, RunOptions::default(), Some(&mut callback));
+        let num_sends = events
+            .iter()
+            .take_while(|(event, _)| matches!(event.action, Action::SendCard { .. }))
+            .count();
+        assert_eq!(num_sends, 6);
+        assert!(events[..num_sends]
+            .iter()
+            .all(|&(_, history_len)| history_len == 0));
+        // each ingame callback comes after its action is in the history
+        for (idx, &(event, history_len)) in events[num_sends..].iter().enumerate() {
+            assert!(!matches!(event.action, Action::SendCard { .. }));
+            assert_eq!(history_len, idx + 1);
+        }
+    }
+
+    // plays the first offered action, remembering what it was offered
+    #[derive(Debug)]
+    struct Recorder(Rc<RefCell<Vec<Vec<Action>>>>);
+
+    impl Strategy for Recorder {
+        fn select_action(
+            &mut self,
+            _private_info: &PlayerState,
+            _public_info: &PublicInfo,
+            available_actions: &[Action],
+        ) -> Action {
+            self.0.borrow_mut().push(available_actions.to_vec());
+            available_actions[0]
+        }
+    }
+
+    #[test]
+    fn auto_pass_skips_the_strategy_when_passing_is_forced() {
+        // p0 can't beat p2's ace, and p2 goes out on the next lead
+        let offers_with = |auto_pass| {
+            let offers = Rc::new(RefCell::new(Vec::new()));
+            let players = ["3C 4C", "KC", "AC 5C"]
+                .into_iter()
+                .enumerate()
+                .map(|(idx, hand)| {
+                    let cards = hand
+                        .split_whitespace()
+                        .map(|card_str| DOCCard::from_str(card_str).expect("Valid card").into())
+                        .collect::<Vec<Card>>();
+                    let strategy = Box::new(Recorder(offers.clone())) as Box<dyn Strategy>;
+                    (Uuid::new_v4(), format!("p{idx}"), cards, strategy)
+                })
+                .collect();
+            let mut game_state = GameState::new_with_hands(players, None).unwrap();
+            let options = RunOptions {
+                auto_pass,
+                ..RunOptions::default()
+            };
+            let mut num_passes = 0;
+            let mut count_passes = |event: &Event, _: &PublicInfo| {
+                num_passes += usize::from(event.action == Action::Pass);
+            };
+            run_game(&mut game_state, options, Some(&mut count_passes));
+            assert_eq!(num_passes, 1);
+            offers.take()
+        };
+        let forced_pass =
+            |offers: &[Vec<Action>]| offers.iter().any(|offer| offer == &[Action::Pass]);
+        assert!(!forced_pass(&offers_with(true)));
+        assert!(forced_pass(&offers_with(false)));
+    }
 }
@@ -0,0 +1,133 @@
+use std::str::FromStr;
+
+use strategies::{DefaultStrategy, EndgameStrategy, InputStrategy, PassStrategy, RandomStrategy};
+use types::Strategy;
+
+// only lives long enough to be boxed into a Player
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug)]
+pub enum Strategies {
+    Default(DefaultStrategy),
+    Random(RandomStrategy),
+    Input(InputStrategy),
+    Endgame(EndgameStrategy),
+    Pass(PassStrategy),
+}
+
+impl FromStr for Strategies {
+    type Err = String; // TODO
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "default" => Ok(Strategies::Default(DefaultStrategy::default())),
+            "random" => Ok(Strategies::Random(RandomStrategy::default())),
+            "input" => Ok(Strategies::Input(InputStrategy::default())),
+            "endgame" => Ok(Strategies::Endgame(EndgameStrategy::default())),
+            "pass" => Ok(Strategies::Pass(PassStrategy::default())),
+            _ => Err(format!("Unable to parse {s:?} to Strategy impl")),
+        }
+    }
+}
+
+impl Strategies {
+    pub fn seeded(self, seed: u64) -> Self {
+        match self {
+            Strategies::Random(_) => Strategies::Random(RandomStrategy::with_seed(seed)),
+            strat => strat,
+        }
+    }
+
+    pub fn with_params(self, params: &serde_json::Value) -> Result<Self, String> {
+        match self {
+            Strategies::Random(_) => match params.get("seed") {
+                Some(seed) => seed
+                    .as_u64()
+                    .map(|seed| Strategies::Random(RandomStrategy::with_seed(seed)))
+                    .ok_or_else(|| format!("Random strategy seed must be a u64, got {seed}")),
+                None => Ok(self),
+            },
+            Strategies::Endgame(_) => match params.get("threshold") {
+                Some(threshold) => threshold
+                    .as_u64()
+                    .map(|threshold| {
+                        Strategies::Endgame(EndgameStrategy::with_threshold(threshold as usize))
+                    })
+                    .ok_or_else(|| format!("Endgame threshold must be a u64, got {threshold}")),
+                None => Ok(self),
+            },
+            Strategies::Input(strat) => {
+                let flag = |name: &str| match params.get(name) {
+                    Some(value) => value
+                        .as_bool()
+                        .ok_or_else(|| format!("Input {name} must be a bool, got {value}")),
+                    None => Ok(false),
+                };
+                Ok(Strategies::Input(
+                    strat
+                        .with_color(flag("color")?)
+                        .with_confirm(flag("confirm")?),
+                ))
+            }
+            strat => {
+                log::warn!("Ignoring params for strategy that takes none: {params}");
+                Ok(strat)
+            }
+        }
+    }
+}
+
+impl From<Strategies> for Box<dyn Strategy> {
+    fn from(value: Strategies) -> Self {
+        match value {
+            Strategies::Default(strat) => Box::new(strat) as Box<dyn Strategy>,
+            Strategies::Random(strat) => Box::new(strat) as Box<dyn Strategy>,
+            Strategies::Input(strat) => Box::new(strat) as Box<dyn Strategy>,
+            Strategies::Endgame(strat) => Box::new(strat) as Box<dyn Strategy>,
+            Strategies::Pass(strat) => Box::new(strat) as Box<dyn Strategy>,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn with_params(name: &str, params: serde_json::Value) -> Result<Strategies, String> {
+        name.parse::<Strategies>()?.with_params(&params)
+    }
+
+    #[test]
+    fn applies_params_for_each_strategy() {
+        assert!(matches!(
+            with_params("random", json!({"seed": 3})),
+            Ok(Strategies::Random(_))
+        ));
+        let endgame = with_params("endgame", json!({"threshold": 6})).unwrap();
+        assert!(format!("{endgame:?}").contains("threshold: 6"));
+        let input = with_params("input", json!({"confirm": true})).unwrap();
+        assert!(format!("{input:?}").contains("confirm: true"));
+        // strategies without params ignore them
+        assert!(matches!(
+            with_params("default", json!({"seed": 3})),
+            Ok(Strategies::Default(_))
+        ));
+    }
+
+    #[test]
+    fn strategies_are_named_what_they_are_registered_as() {
+        for name in ["default", "random", "input", "endgame", "pass"] {
+            let strategy: Box<dyn Strategy> = name.parse::<Strategies>().unwrap().into();
+            assert_eq!(strategy.name(), name);
+        }
+        assert!("unknown".parse::<Strategies>().is_err());
+    }
+
+    #[test]
+    fn rejects_params_of_the_wrong_type() {
+        assert!(with_params("random", json!({"seed": "three"})).is_err());
+        assert!(with_params("endgame", json!({"threshold": -1})).is_err());
+        assert!(with_params("input", json!({"color": "yes"})).is_err());
+    }
+}
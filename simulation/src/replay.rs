@@ -0,0 +1,119 @@
+use types::{
+    game_state::PublicInfo, Action, Event, GameConfig, GameState, PlayerState, ReplayError,
+    Strategy,
+};
+
+#[derive(Debug)]
+struct ReplayStrategy;
+
+impl Strategy for ReplayStrategy {
+    fn select_action(
+        &mut self,
+        _private_info: &PlayerState,
+        _public_info: &PublicInfo,
+        _available_actions: &[Action],
+    ) -> Action {
+        panic!("Replays apply recorded actions and never ask a strategy to choose")
+    }
+}
+
+/// Rebuilds the first game of a session seeded with `seed`, with players listed in the same order
+/// they were originally given, and checks that each recorded ingame event was the acting
+/// player's turn and a permitted action. Returns the first divergence.
+pub fn validate_replay(
+    player_names: &[String],
+    config: GameConfig,
+    seed: u64,
+    events: &[Event],
+) -> Result<(), ReplayError> {
+    let player_inputs = player_names
+        .iter()
+        .map(|name| (name.clone(), Box::new(ReplayStrategy) as Box<dyn Strategy>))
+        .collect();
+    GameState::new_with_seed(player_inputs, config, seed)?.replay_events(events)
+}
+
+#[cfg(test)]
+mod tests {
+    use types::game_state::PublicInfo;
+
+    use super::*;
+    use crate::{run_game, RunOptions, Strategies};
+
+    const SEED: u64 = 5;
+
+    fn names() -> Vec<String> {
+        (0..4).map(|idx| format!("p{idx}")).collect()
+    }
+
+    // the first game of a seeded session between random players
+    fn recorded_game() -> Vec<Event> {
+        let player_inputs = names()
+            .into_iter()
+            .enumerate()
+            .map(|(idx, name)| {
+                let strategy = "random"
+                    .parse::<Strategies>()
+                    .expect("Registered strategy")
+                    .seeded(idx as u64);
+                (name, strategy.into())
+            })
+            .collect();
+        let mut game_state =
+            GameState::new_with_seed(player_inputs, GameConfig::default(), SEED).unwrap();
+        let mut events = Vec::new();
+        let mut callback = |event: &Event, _: &PublicInfo| events.push(*event);
+        run_game(&mut game_state, RunOptions::default(), Some(&mut callback));
+        events
+    }
+
+    fn validate(events: &[Event]) -> Result<(), ReplayError> {
+        validate_replay(&names(), GameConfig::default(), SEED, events)
+    }
+
+    #[test]
+    fn accepts_a_recorded_game() {
+        let events = recorded_game();
+        assert!(events.len() > 13);
+        assert_eq!(validate(&events), Ok(()));
+        assert_eq!(validate(&events[..10]), Ok(()));
+    }
+
+    #[test]
+    fn reports_the_first_tampered_turn() {
+        let events = recorded_game();
+        let mut not_permitted = events.clone();
+        not_permitted[3].action = events[0].action;
+        assert_eq!(
+            validate(&not_permitted),
+            Err(ReplayError::NotPermitted {
+                turn: 4,
+                event: not_permitted[3]
+            })
+        );
+
+        let mut out_of_turn = events.clone();
+        out_of_turn[5].player_id = events[4].player_id;
+        assert_eq!(
+            validate(&out_of_turn),
+            Err(ReplayError::OutOfTurn {
+                turn: 6,
+                event: out_of_turn[5],
+                expected_player_id: events[5].player_id
+            })
+        );
+
+        let mut after_the_end = events.clone();
+        after_the_end.push(Event {
+            player_id: events[0].player_id,
+            action: Action::Pass,
+        });
+        assert_eq!(
+            validate(&after_the_end),
+            Err(ReplayError::GameOver {
+                turn: events.len() + 1,
+                event: after_the_end[events.len()]
+            })
+        );
+    }
+}
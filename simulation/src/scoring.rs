@@ -0,0 +1,64 @@
+use std::collections::HashMap;
+
+use types::Role;
+use uuid::Uuid;
+
+#[derive(Clone, Debug)]
+pub struct ScoringConfig {
+    /// Points for each finishing place, starting with first. Places past the end score 0.
+    pub points_by_place: Vec<i32>,
+}
+
+impl ScoringConfig {
+    /// First place gets `num_players - 1` points, down to 0 for last place
+    pub fn linear(num_players: usize) -> Self {
+        Self {
+            points_by_place: (0..num_players as i32).rev().collect(),
+        }
+    }
+
+    pub fn points_for_place(&self, place: usize) -> i32 {
+        place
+            .checked_sub(1)
+            .and_then(|idx| self.points_by_place.get(idx))
+            .copied()
+            .unwrap_or(0)
+    }
+}
+
+pub fn score_game(standings: &[(Uuid, usize, Role)], config: &ScoringConfig) -> HashMap<Uuid, i32> {
+    standings
+        .iter()
+        .map(|&(player_id, place, _)| (player_id, config.points_for_place(place)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn linear_scoring_counts_down_to_zero() {
+        let scoring = ScoringConfig::linear(4);
+        assert_eq!(scoring.points_by_place, [3, 2, 1, 0]);
+        assert_eq!(scoring.points_for_place(0), 0);
+        assert_eq!(scoring.points_for_place(5), 0);
+    }
+
+    #[test]
+    fn scores_each_player_by_place() {
+        let scoring = ScoringConfig {
+            points_by_place: vec![10, 5, -5],
+        };
+        let ids = [Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4()];
+        let standings = [
+            (ids[0], 1, Role::President),
+            (ids[1], 3, Role::Asshole),
+            (ids[2], 3, Role::Asshole),
+        ];
+        let points = score_game(&standings, &scoring);
+        assert_eq!(points[&ids[0]], 10);
+        assert_eq!(points[&ids[1]], -5);
+        assert_eq!(points[&ids[2]], -5);
+    }
+}
@@ -0,0 +1,215 @@
+use types::{game_state::PublicInfo, hand::Hand, Action, Card, CardPlay, PlayerState, Strategy};
+
+use crate::DefaultStrategy;
+
+/// Plays like `DefaultStrategy` until its hand is at most `threshold` cards, then searches
+/// for the action that empties the hand in the fewest turns.
+#[derive(Debug)]
+pub struct EndgameStrategy {
+    threshold: usize,
+    fallback: DefaultStrategy,
+}
+
+impl Default for EndgameStrategy {
+    fn default() -> Self {
+        Self::with_threshold(4)
+    }
+}
+
+impl EndgameStrategy {
+    pub fn with_threshold(threshold: usize) -> Self {
+        Self {
+            threshold,
+            fallback: DefaultStrategy::default(),
+        }
+    }
+}
+
+impl Strategy for EndgameStrategy {
+    fn name(&self) -> &'static str {
+        "endgame"
+    }
+
+    fn select_action(
+        &mut self,
+        private_info: &PlayerState,
+        public_info: &PublicInfo,
+        available_actions: &[Action],
+    ) -> Action {
+        let hand = &private_info.current_hand;
+        let is_ingame = available_actions
+            .iter()
+            .all(|action| !matches!(action, Action::SendCard { .. }));
+        if !is_ingame || hand.len() > self.threshold {
+            return self
+                .fallback
+                .select_action(private_info, public_info, available_actions);
+        }
+
+        log::debug!(
+            "{} can empty their hand in {} turns",
+            private_info.name,
+            turns_to_empty(hand, public_info.top_card, public_info.reversed)
+        );
+        // whatever we do, the pile is empty and unreversed by our next turn, so only the cards
+        // left matter. Ties shed the lowest cards, which are weakest again once the pile clears.
+        *available_actions
+            .iter()
+            .min_by_key(|action| match action {
+                Action::PlayCards { card_play } => {
+                    let rest = without_cards(hand, card_play);
+                    (1 + turns_to_empty(&rest, None, false), card_play.value())
+                }
+                Action::PlayThenComplete { first, complete } => {
+                    let rest = without_cards(&without_cards(hand, first), complete);
+                    (1 + turns_to_empty(&rest, None, false), first.value())
+                }
+                _ => (1 + turns_to_empty(hand, None, false), usize::MAX),
+            })
+            .expect("Should always have at least one action to choose from")
+    }
+}
+
+/// Fewest turns needed to empty `hand` when facing `top_card`, with lower plays beating it if
+/// `reversed`, assuming the pile comes back to us empty and unreversed after each of our turns.
+/// Exhaustive, so only meant for small hands.
+pub fn turns_to_empty(hand: &[Card], top_card: Option<CardPlay>, reversed: bool) -> usize {
+    if hand.is_empty() {
+        return 0;
+    }
+    let hand = hand.to_vec();
+    let plays = match top_card {
+        None => [hand.singles(), hand.pairs(), hand.triples(), hand.quads()].concat(),
+        Some(top) => match top {
+            CardPlay::Single(..) => hand.singles(),
+            CardPlay::Pair(..) => hand.pairs(),
+            CardPlay::Triple(..) => hand.triples(),
+            CardPlay::Quad(..) => hand.quads(),
+        }
+        .into_iter()
+        .filter(|&cp| if reversed { cp < top } else { cp > top })
+        .collect(),
+    };
+    let best_play = plays
+        .iter()
+        .map(|cp| 1 + turns_to_empty(&without_cards(&hand, cp), None, false))
+        .min();
+    let pass = top_card.map(|_| 1 + turns_to_empty(&hand, None, false));
+    best_play
+        .into_iter()
+        .chain(pass)
+        .min()
+        .expect("Can always play from a non-empty hand onto an empty pile")
+}
+
+fn without_cards(hand: &[Card], card_play: &CardPlay) -> Vec<Card> {
+    let mut rest = hand.to_vec();
+    for card in card_play.to_vec() {
+        rest.remove_card(&card);
+    }
+    rest
+}
+
+#[cfg(test)]
+mod tests {
+    use deckofcards::Card as DOCCard;
+    use types::GameState;
+    use uuid::Uuid;
+
+    use super::*;
+
+    fn cards(cards_str: &str) -> Vec<Card> {
+        cards_str
+            .split_whitespace()
+            .map(|card_str| DOCCard::from_str(card_str).expect("Valid card").into())
+            .collect()
+    }
+
+    fn single(card_str: &str) -> CardPlay {
+        CardPlay::Single(cards(card_str)[0])
+    }
+
+    #[test]
+    fn counts_turns_to_empty_a_hand() {
+        assert_eq!(turns_to_empty(&[], None, false), 0);
+        assert_eq!(turns_to_empty(&cards("3C 4C 5C"), None, false), 3);
+        assert_eq!(turns_to_empty(&cards("5C 5S 9H"), None, false), 2);
+        assert_eq!(turns_to_empty(&cards("5C 5S 5H 5D KH"), None, false), 2);
+        // beating the top card saves a turn over passing
+        assert_eq!(
+            turns_to_empty(&cards("5C 5S 9H"), Some(single("6C")), false),
+            2
+        );
+        assert_eq!(
+            turns_to_empty(&cards("5C 5S 4H"), Some(single("6C")), false),
+            3
+        );
+        // after a reversing quad, only a lower quad beats it
+        let sixes = CardPlay::from_cards(&cards("6C 6S 6H 6D").iter().collect::<Vec<_>>());
+        let hand = cards("5C 5S 5H 5D 9H");
+        assert_eq!(turns_to_empty(&hand, Some(sixes), true), 2);
+        assert_eq!(turns_to_empty(&hand, Some(sixes), false), 3);
+    }
+
+    #[test]
+    fn plays_toward_the_fewest_turns_once_under_the_threshold() {
+        let hand = cards("5C 5S 9H");
+        let players = vec![
+            (
+                Uuid::new_v4(),
+                "endgame".to_string(),
+                hand.clone(),
+                Box::new(EndgameStrategy::default()) as Box<dyn Strategy>,
+            ),
+            (
+                Uuid::new_v4(),
+                "other".to_string(),
+                cards("KC"),
+                Box::new(EndgameStrategy::default()) as Box<dyn Strategy>,
+            ),
+        ];
+        let mut game_state = GameState::new_with_hands(players, None).unwrap();
+        let available_actions = game_state.permitted_actions();
+        let public_info = game_state.public_info();
+        let player = game_state.current_player_mut();
+        // the pair and the 9 both leave one turn to go, and the pair is worth less
+        let pair = CardPlay::from_cards(&hand[..2].iter().collect::<Vec<_>>());
+        assert_eq!(
+            player
+                .strategy
+                .select_action(&player.state, &public_info, &available_actions),
+            Action::PlayCards { card_play: pair }
+        );
+    }
+
+    #[test]
+    fn beats_a_known_top_card_when_that_empties_the_hand_fastest() {
+        let players = vec![
+            (
+                Uuid::new_v4(),
+                "endgame".to_string(),
+                cards("5C 5S 9H"),
+                Box::new(EndgameStrategy::default()) as Box<dyn Strategy>,
+            ),
+            (
+                Uuid::new_v4(),
+                "other".to_string(),
+                cards("KC"),
+                Box::new(EndgameStrategy::default()) as Box<dyn Strategy>,
+            ),
+        ];
+        let mut game_state = GameState::new_with_hands(players, Some(single("6C"))).unwrap();
+        let available_actions = game_state.permitted_actions();
+        let public_info = game_state.public_info();
+        let player = game_state.current_player_mut();
+        // the 9 leaves only the pair, while passing leaves all three cards
+        assert_eq!(
+            player
+                .strategy
+                .select_action(&player.state, &public_info, &available_actions),
+            Action::PlayCards {
+                card_play: single("9H")
+            }
+        );
+    }
+}
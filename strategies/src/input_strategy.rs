@@ -1,40 +1,109 @@
-use std::io::{self, Write};
+use std::{
+    fmt::Display,
+    io::{self, BufRead, Write},
+};
 
 use deckofcards::{Rank, Suit};
 use itertools::Itertools;
 use regex::{Captures, Regex};
-use types::{game_state::PublicInfo, Action, PlayerState, Strategy};
+use types::{game_state::PublicInfo, Action, CardPlay, PlayerState, Strategy};
+
+const BOLD: &str = "1";
+const CYAN: &str = "36";
+const YELLOW: &str = "33";
 
 #[derive(Debug, Default)]
-pub struct InputStrategy {}
+pub struct InputStrategy {
+    color: bool,
+    confirm: bool,
+}
+
+impl InputStrategy {
+    /// Colorizes the prompt, own hand and top card with ANSI codes; ignored if `NO_COLOR` is set
+    pub fn with_color(mut self, enabled: bool) -> Self {
+        self.color = enabled && std::env::var_os("NO_COLOR").is_none();
+        self
+    }
+
+    /// Asks for confirmation before playing any 2s or a quad
+    pub fn with_confirm(mut self, enabled: bool) -> Self {
+        self.confirm = enabled;
+        self
+    }
+
+    fn paint(&self, text: impl Display, code: &str) -> String {
+        if self.color {
+            format!("\x1b[{code}m{text}\x1b[0m")
+        } else {
+            text.to_string()
+        }
+    }
+}
 
 impl Strategy for InputStrategy {
+    fn name(&self) -> &'static str {
+        "input"
+    }
+
     fn select_action(
         &mut self,
         private_info: &PlayerState,
         public_info: &PublicInfo,
         available_actions: &[Action],
     ) -> Action {
-        print_public_info(&public_info);
-        println!("Private info: {private_info}");
+        for line in public_info.player_lines() {
+            println!("{line}");
+        }
+        // highlighted to stand out from the table
+        let top_card_str = public_info
+            .top_card
+            .map_or("None".to_string(), |cp| cp.to_string());
+        println!(
+            "{}",
+            self.paint(format!("Top card is: {top_card_str}"), YELLOW)
+        );
+        println!("Private info: {}", self.paint(private_info, CYAN));
         println!(
             "Available actions: {}",
-            available_actions.iter().sorted().join(" || ")
+            available_actions
+                .iter()
+                .sorted()
+                .sorted_by_key(|action| display_order(action))
+                .join(" || ")
         );
 
         // if only one available action, do it
         if available_actions.len() == 1 {
             let action = *available_actions
-                .get(0)
+                .first()
                 .expect("Guaranteed to have an action available");
             log::info!("Only have one action available: {action}");
             return action;
         }
 
+        self.read_action(&mut io::stdin().lock(), available_actions)
+    }
+}
+
+impl InputStrategy {
+    // prompts until `input` names one of `available_actions`, confirmed if need be
+    fn read_action(&self, input: &mut impl BufRead, available_actions: &[Action]) -> Action {
         let mut buf = String::new();
         loop {
-            match select_action_from_stdin(&mut buf, available_actions) {
-                Ok(action) => return action,
+            match select_action_from_input(
+                input,
+                &mut buf,
+                available_actions,
+                &self.paint("Your action? >> ", BOLD),
+            ) {
+                Ok(action) if !self.confirm || !needs_confirmation(&action) => return action,
+                Ok(action) => {
+                    buf.clear();
+                    if confirm_from_input(input, &mut buf, &action) {
+                        return action;
+                    }
+                    buf.clear();
+                }
                 Err(err) => {
                     buf.clear();
                     log::error!("Error parsing message from stdin: {err}")
@@ -44,45 +113,26 @@ impl Strategy for InputStrategy {
     }
 }
 
-fn print_public_info(info: &PublicInfo) {
-    for player_pub_info in info.public_table.iter() {
-        let card_plays: Vec<_> = info
-            .history
-            .iter()
-            .filter_map(|event| {
-                if event.player_id != player_pub_info.id {
-                    return None;
-                }
-                match event.action {
-                    Action::PlayCards { card_play } => Some(card_play),
-                    _ => return None,
-                }
-            })
-            .collect();
-        println!(
-            "{} ({}) has {} cards left and has played: [ {} ]",
-            player_pub_info.name,
-            player_pub_info
-                .role
-                .map(|r| r.to_string())
-                .unwrap_or("None".to_string()),
-            player_pub_info.hand_size,
-            card_plays.iter().sorted().join(", ")
-        );
+// weakest plays first, grouped by size, with Pass at the end; `Action`'s own `Ord` is untouched
+fn display_order(action: &Action) -> (usize, usize, usize) {
+    match action {
+        Action::SendCard { card, .. } => (0, 0, card.value()),
+        Action::PlayCards { card_play } => (1, card_play.size(), card_play.value()),
+        Action::PlayThenComplete { first, .. } => (2, first.size(), first.value()),
+        Action::Pass => (3, 0, 0),
     }
-    println!(
-        "Top card is: {}",
-        info.top_card
-            .map(|cp| cp.to_string())
-            .unwrap_or("None".to_string())
-    );
 }
 
-fn select_action_from_stdin(buf: &mut String, actions: &[Action]) -> Result<Action, String> {
-    print!("Your action? >> ");
+fn select_action_from_input(
+    input: &mut impl BufRead,
+    buf: &mut String,
+    actions: &[Action],
+    prompt: &str,
+) -> Result<Action, String> {
+    print!("{prompt}");
     let _ = io::stdout().flush();
-    match io::stdin().read_line(buf) {
-        Ok(_) => select_action_from_str(&buf, actions),
+    match input.read_line(buf) {
+        Ok(_) => select_action_from_str(buf, actions),
         Err(err) => {
             buf.clear();
             Err(format!("Error reading line from stdin: {err}"))
@@ -90,6 +140,25 @@ fn select_action_from_stdin(buf: &mut String, actions: &[Action]) -> Result<Acti
     }
 }
 
+fn needs_confirmation(action: &Action) -> bool {
+    action
+        .card_plays()
+        .iter()
+        .any(|card_play| card_play.rank() == Rank::Two || matches!(card_play, CardPlay::Quad(..)))
+}
+
+fn confirm_from_input(input: &mut impl BufRead, buf: &mut String, action: &Action) -> bool {
+    print!("{action}: Are you sure? (y/n) >> ");
+    let _ = io::stdout().flush();
+    match input.read_line(buf) {
+        Ok(_) => matches!(buf.trim().to_lowercase().as_str(), "y" | "yes"),
+        Err(err) => {
+            log::error!("Error reading line from stdin: {err}");
+            false
+        }
+    }
+}
+
 fn select_action_from_str(input: &str, actions: &[Action]) -> Result<Action, String> {
     let input = input.to_lowercase();
     let input = input.as_str();
@@ -112,6 +181,16 @@ fn select_action_from_str(input: &str, actions: &[Action]) -> Result<Action, Str
         return pass_result;
     }
 
+    // before plain plays, which would otherwise read "then" as a card
+    if let Some(double_down_result) = _get_action_from_regex(
+        input,
+        actions,
+        Regex::new(r"play (.+) then (.+)").expect("Valid double down regex"),
+        double_down_action_from_captures,
+    ) {
+        return double_down_result;
+    }
+
     if let Some(play_result) = _get_action_from_regex(
         input,
         actions,
@@ -132,9 +211,7 @@ fn _get_action_from_regex(
     re: Regex,
     callback: for<'a, 'b, 'c> fn(&'a regex::Captures<'b>, &'c [Action]) -> Result<Action, String>,
 ) -> Option<Result<Action, String>> {
-    let Some(caps) = re.captures(input) else {
-        return None;
-    };
+    let caps = re.captures(input)?;
 
     Some(callback(&caps, actions))
 }
@@ -147,20 +224,19 @@ fn send_action_from_captures(caps: &Captures, actions: &[Action]) -> Result<Acti
     )?;
     actions
         .iter()
-        .filter(|act| {
+        .find(|act| {
             if let Action::SendCard { card, .. } = act {
                 card.rank() == rank && suit.map(|s| s == card.suit()).unwrap_or(true)
             } else {
                 false
             }
         })
-        .next()
         .copied()
         .ok_or_else(|| format!("No card matching {rank:?}"))
 }
 
 fn pass_action_from_captures(_caps: &Captures, actions: &[Action]) -> Result<Action, String> {
-    if actions.iter().any(|act| *act == Action::Pass) {
+    if actions.contains(&Action::Pass) {
         Ok(Action::Pass)
     } else {
         Err("Attempted to pass at a time when passing is not a permitted action".to_string())
@@ -170,28 +246,72 @@ fn pass_action_from_captures(_caps: &Captures, actions: &[Action]) -> Result<Act
 fn play_action_from_captures(caps: &Captures, actions: &[Action]) -> Result<Action, String> {
     log::debug!("Captured: {caps:?}");
     let input = caps.get(0).expect("Group 0 is guaranteed").as_str();
-    let mut play_str = caps
-        .get(1)
-        .expect("Group 1 is required in play regex")
-        .as_str();
+    let cards = parse_cards(
+        caps.get(1)
+            .expect("Group 1 is required in play regex")
+            .as_str(),
+    )?;
+
+    let &card_play = actions
+        .iter()
+        .filter_map(|act| {
+            if let Action::PlayCards { card_play } = act {
+                Some(card_play)
+            } else {
+                None
+            }
+        })
+        .find(|cp| card_play_matches(cp, &cards))
+        .ok_or_else(|| "Unable to find a permitted action matching the input string".to_string())?;
 
+    log::debug!("From actions {actions:?}, given string {input:?}, selected {card_play:?}");
+
+    Ok(Action::PlayCards { card_play })
+}
+
+fn double_down_action_from_captures(caps: &Captures, actions: &[Action]) -> Result<Action, String> {
+    log::debug!("Captured: {caps:?}");
+    let first_cards = parse_cards(caps.get(1).expect("Group 1 is required").as_str())?;
+    let complete_cards = parse_cards(caps.get(2).expect("Group 2 is required").as_str())?;
+    // each double down is listed once, with its two sets in either order
+    actions
+        .iter()
+        .find(|act| {
+            if let Action::PlayThenComplete { first, complete } = act {
+                (card_play_matches(first, &first_cards)
+                    && card_play_matches(complete, &complete_cards))
+                    || (card_play_matches(first, &complete_cards)
+                        && card_play_matches(complete, &first_cards))
+            } else {
+                false
+            }
+        })
+        .copied()
+        .ok_or_else(|| {
+            "Unable to find a permitted double down matching the input string".to_string()
+        })
+}
+
+// cards of one rank, each with an optional suit, e.g. "5c, 5" or "5c 5s"
+fn parse_cards(play_str: &str) -> Result<Vec<(Rank, Option<Suit>)>, String> {
     let muncher_re =
         Regex::new(r"(?<card>\w+)(?:[,\s]\s*(?<tail>.*))?").expect("Valid play muncher regex");
+    let mut rest = play_str;
     let mut cards = Vec::new();
-    while let Some(caps) = muncher_re.captures(play_str) {
+    while let Some(caps) = muncher_re.captures(rest) {
         log::debug!("Captured: {caps:?}");
         let card = parse_card(
             caps.name("card")
                 .expect("card is a required group")
                 .as_str(),
         )?;
-        play_str = caps.name("tail").map(|m| m.as_str()).unwrap_or("");
+        rest = caps.name("tail").map(|m| m.as_str()).unwrap_or("");
         cards.push(card);
     }
 
     if cards.is_empty() {
         return Err(format!(
-            "Attempted to play cards, but no cards found in string: {input:?}"
+            "Attempted to play cards, but no cards found in string: {play_str:?}"
         ));
     }
 
@@ -206,40 +326,23 @@ fn play_action_from_captures(caps: &Captures, actions: &[Action]) -> Result<Acti
     let suit_counts = cards.iter().filter_map(|c| c.1).counts_by(|c| c);
     if let Some((mode_suit, mode_suit_count)) = suit_counts.iter().max_by_key(|s| s.1) {
         if *mode_suit_count > 1 {
-            let rank = cards.get(0).expect("Guaranteed to have at least 1 card").0;
+            let rank = cards.first().expect("Guaranteed to have at least 1 card").0;
             return Err(format!(
                     "Attempted to play multiple cards of rank {rank:?}, but {mode_suit_count} are the same suit {mode_suit:?}"
                     ));
         }
     }
+    Ok(cards)
+}
 
-    let rank = cards.get(0).expect("Guaranteed at least one card").0;
-    let &card_play = actions
-        .iter()
-        .filter_map(|act| {
-            if let Action::PlayCards { card_play } = act {
-                Some(card_play)
-            } else {
-                None
-            }
+// right number of cards, rank matches and all given suits are accounted for
+fn card_play_matches(card_play: &CardPlay, cards: &[(Rank, Option<Suit>)]) -> bool {
+    let cp_cards = card_play.to_vec();
+    card_play.size() == cards.len()
+        && cards.iter().all(|&(rank, suit)| {
+            card_play.rank() == rank
+                && suit.is_none_or(|suit| cp_cards.iter().any(|c| c.suit() == suit))
         })
-        // correct # of cards?
-        .filter(|cp| cp.size() == cards.len())
-        // rank matches and all suits are accounted for?
-        .filter(|cp| {
-            let cp_cards = cp.to_vec();
-            let suits: Vec<Suit> = cards.iter().filter_map(|c| c.1).collect();
-            cp.rank() == rank
-                && suits
-                    .iter()
-                    .all(|suit| cp_cards.iter().any(|c| c.suit() == *suit))
-        })
-        .next()
-        .ok_or_else(|| format!("Unable to find a permitted action matching the input string"))?;
-
-    log::debug!("From actions {actions:?}, given string {input:?}, selected {card_play:?}");
-
-    return Ok(Action::PlayCards { card_play });
 }
 
 fn parse_card(card_str: &str) -> Result<(Rank, Option<Suit>), String> {
@@ -248,8 +351,7 @@ fn parse_card(card_str: &str) -> Result<(Rank, Option<Suit>), String> {
         return Err(format!("Unable to parse card from {card_str}"));
     };
     let rank = rank_from_rank_str(
-        &caps
-            .name("rank")
+        caps.name("rank")
             .expect("Rank should always exist for send")
             .as_str(),
     )?;
@@ -288,3 +390,153 @@ fn suit_from_suit_str(s: &str) -> Result<Suit, String> {
         _ => Err(format!("Unable to convert string to suit: {s}")),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use types::Card;
+
+    use super::*;
+
+    fn pair(rank: Rank, suits: [Suit; 2]) -> CardPlay {
+        let cards = suits.map(|suit| Card::new(rank, suit));
+        CardPlay::from_cards(&[&cards[0], &cards[1]])
+    }
+
+    fn double_down_actions() -> Vec<Action> {
+        let low = pair(Rank::Five, [Suit::Clubs, Suit::Spades]);
+        let high = pair(Rank::Five, [Suit::Hearts, Suit::Diamonds]);
+        vec![
+            Action::PlayCards { card_play: low },
+            Action::PlayCards { card_play: high },
+            Action::Pass,
+            Action::PlayThenComplete {
+                first: low,
+                complete: high,
+            },
+        ]
+    }
+
+    #[test]
+    fn parses_double_down_in_either_order() {
+        let actions = double_down_actions();
+        let double_down = actions[3];
+        assert_eq!(
+            select_action_from_str("play 5c 5s then 5h 5d", &actions),
+            Ok(double_down)
+        );
+        assert_eq!(
+            select_action_from_str("play 5h,5d then 5c,5s", &actions),
+            Ok(double_down)
+        );
+        assert!(select_action_from_str("play 5c 5h then 5s 5d", &actions).is_err());
+    }
+
+    #[test]
+    fn plain_play_is_not_a_double_down() {
+        let actions = double_down_actions();
+        assert_eq!(
+            select_action_from_str("play 5h 5d", &actions),
+            Ok(actions[1])
+        );
+        assert_eq!(select_action_from_str("pass", &actions), Ok(Action::Pass));
+    }
+
+    #[test]
+    fn paints_only_when_color_is_on() {
+        let plain = InputStrategy::default();
+        assert_eq!(plain.paint("top", YELLOW), "top");
+        assert_eq!(
+            InputStrategy::default()
+                .with_color(false)
+                .paint("top", YELLOW),
+            "top"
+        );
+        let colored = InputStrategy {
+            color: true,
+            ..InputStrategy::default()
+        };
+        assert_eq!(colored.paint("top", YELLOW), "\x1b[33mtop\x1b[0m");
+    }
+
+    #[test]
+    fn asks_to_confirm_twos_and_quads() {
+        let single = |rank| Action::PlayCards {
+            card_play: CardPlay::Single(Card::new(rank, Suit::Clubs)),
+        };
+        let fives = Suit::iterator()
+            .map(|&suit| Card::new(Rank::Five, suit))
+            .collect_vec();
+        let quad = CardPlay::from_cards(&fives.iter().collect_vec());
+        let low_twos = pair(Rank::Two, [Suit::Clubs, Suit::Spades]);
+        let high_twos = pair(Rank::Two, [Suit::Hearts, Suit::Diamonds]);
+        assert!(needs_confirmation(&single(Rank::Two)));
+        assert!(needs_confirmation(&Action::PlayCards { card_play: quad }));
+        assert!(needs_confirmation(&Action::PlayThenComplete {
+            first: low_twos,
+            complete: high_twos
+        }));
+        assert!(!needs_confirmation(&single(Rank::Ace)));
+        assert!(!needs_confirmation(&double_down_actions()[3]));
+        assert!(!needs_confirmation(&Action::Pass));
+    }
+
+    #[test]
+    fn confirmed_twos_are_played_and_declined_ones_prompt_again() {
+        let single = |rank| Action::PlayCards {
+            card_play: CardPlay::Single(Card::new(rank, Suit::Clubs)),
+        };
+        let actions = [single(Rank::Four), single(Rank::Two), Action::Pass];
+        let strategy = InputStrategy::default().with_confirm(true);
+
+        let mut input = "play 2c\ny\n".as_bytes();
+        assert_eq!(
+            strategy.read_action(&mut input, &actions),
+            single(Rank::Two)
+        );
+
+        let mut input = "play 2c\nn\nplay 4c\n".as_bytes();
+        assert_eq!(
+            strategy.read_action(&mut input, &actions),
+            single(Rank::Four)
+        );
+        assert!(input.is_empty());
+
+        // without the flag, 2s go through unasked
+        let mut input = "play 2c\n".as_bytes();
+        let strategy = InputStrategy::default();
+        assert_eq!(
+            strategy.read_action(&mut input, &actions),
+            single(Rank::Two)
+        );
+    }
+
+    #[test]
+    fn lists_weakest_plays_first_and_pass_last() {
+        let single = |rank| Action::PlayCards {
+            card_play: CardPlay::Single(Card::new(rank, Suit::Clubs)),
+        };
+        let fives = double_down_actions();
+        let actions = [
+            Action::Pass,
+            fives[3],
+            single(Rank::Two),
+            fives[0],
+            single(Rank::King),
+        ];
+        let ordered = actions
+            .iter()
+            .sorted_by_key(|action| display_order(action))
+            .copied()
+            .collect_vec();
+        assert_eq!(
+            ordered,
+            [
+                single(Rank::King),
+                single(Rank::Two),
+                fives[0],
+                fives[3],
+                Action::Pass
+            ]
+        );
+    }
+}
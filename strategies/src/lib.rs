@@ -1,17 +1,42 @@
+pub mod endgame_strategy;
 pub mod input_strategy;
+pub mod logging_strategy;
+pub mod pass_strategy;
 
-use deckofcards::{Rank, Suit};
-use rand::{rngs::ThreadRng, seq::SliceRandom};
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
 use types::{Action, Strategy};
 
-pub use crate::input_strategy::InputStrategy;
+pub use crate::{
+    endgame_strategy::EndgameStrategy, input_strategy::InputStrategy,
+    logging_strategy::LoggingStrategy, pass_strategy::PassStrategy,
+};
 
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct RandomStrategy {
-    rng: ThreadRng,
+    rng: StdRng,
+}
+
+impl Default for RandomStrategy {
+    fn default() -> Self {
+        Self {
+            rng: StdRng::from_entropy(),
+        }
+    }
+}
+
+impl RandomStrategy {
+    pub fn with_seed(seed: u64) -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
 }
 
 impl Strategy for RandomStrategy {
+    fn name(&self) -> &'static str {
+        "random"
+    }
+
     fn select_action(
         &mut self,
         _private_info: &types::PlayerState,
@@ -28,6 +53,10 @@ impl Strategy for RandomStrategy {
 pub struct DefaultStrategy {}
 
 impl Strategy for DefaultStrategy {
+    fn name(&self) -> &'static str {
+        "default"
+    }
+
     fn select_action(
         &mut self,
         _private_info: &types::PlayerState,
@@ -54,15 +83,9 @@ impl Strategy for DefaultStrategy {
         // always send worst card
         if let Some(pass_card_action) = available_actions
             .iter()
-            .filter_map(|action| -> Option<(&Action, &types::Card)> {
+            .filter_map(|action| {
                 if let Action::SendCard { card, .. } = action {
-                    let is_three_of_clubs =
-                        card.rank() == Rank::Three && card.suit() == Suit::Clubs;
-                    if is_three_of_clubs {
-                        None
-                    } else {
-                        Some((action, card))
-                    }
+                    Some((action, card))
                 } else {
                     None
                 }
@@ -74,7 +97,7 @@ impl Strategy for DefaultStrategy {
         }
 
         *available_actions
-            .get(0)
+            .first()
             .expect("Always should have an action available when this is called")
     }
 }
@@ -0,0 +1,110 @@
+use itertools::Itertools;
+use types::{game_state::PublicInfo, Action, PlayerState, Strategy};
+
+/// Wraps another strategy and logs each of its decisions, with the options it had, at debug level
+#[derive(Debug, Default)]
+pub struct LoggingStrategy<S: Strategy> {
+    inner: S,
+}
+
+impl<S: Strategy> LoggingStrategy<S> {
+    pub fn new(inner: S) -> Self {
+        Self { inner }
+    }
+
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S: Strategy> Strategy for LoggingStrategy<S> {
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+
+    fn select_action(
+        &mut self,
+        private_info: &PlayerState,
+        public_info: &PublicInfo,
+        available_actions: &[Action],
+    ) -> Action {
+        let action = self
+            .inner
+            .select_action(private_info, public_info, available_actions);
+        log::debug!(
+            "{} chose {action} facing {} from [ {} ]",
+            private_info.name,
+            public_info
+                .top_card
+                .map_or_else(|| "None".to_string(), |cp| cp.to_string()),
+            available_actions.iter().join(", ")
+        );
+        action
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use deckofcards::Card as DOCCard;
+    use types::{Card, CardPlay, GameState};
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::DefaultStrategy;
+
+    thread_local! {
+        static CAPTURED_LOGS: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+    }
+
+    // keeps each test thread's log messages apart, since tests run side by side
+    struct CapturingLogger;
+
+    impl log::Log for CapturingLogger {
+        fn enabled(&self, _metadata: &log::Metadata) -> bool {
+            true
+        }
+
+        fn log(&self, record: &log::Record) {
+            let message = record.args().to_string();
+            CAPTURED_LOGS.with(|logs| logs.borrow_mut().push(message));
+        }
+
+        fn flush(&self) {}
+    }
+
+    #[test]
+    fn logs_the_inner_strategys_choice() {
+        static LOGGER: CapturingLogger = CapturingLogger;
+        let _ = log::set_logger(&LOGGER);
+        log::set_max_level(log::LevelFilter::Debug);
+
+        let card = |card_str| Card::from(DOCCard::from_str(card_str).expect("Valid card"));
+        let players = [["9C", "JC"], ["KC", "AC"]]
+            .into_iter()
+            .enumerate()
+            .map(|(idx, hand)| {
+                (
+                    Uuid::new_v4(),
+                    format!("p{idx}"),
+                    hand.map(card).to_vec(),
+                    Box::new(LoggingStrategy::new(DefaultStrategy::default())) as Box<dyn Strategy>,
+                )
+            })
+            .collect();
+        let top_card = CardPlay::Single(card("8C"));
+        let mut game_state = GameState::new_with_hands(players, Some(top_card)).unwrap();
+        let available_actions = game_state.permitted_actions();
+        let public_info = game_state.public_info();
+        let player = game_state.current_player_mut();
+        assert_eq!(player.strategy.name(), "default");
+        let action = player
+            .strategy
+            .select_action(&player.state, &public_info, &available_actions);
+        assert_ne!(action, Action::Pass);
+        let expected = format!("p0 chose {action} facing (8♣) from [ Play 9♣, Play J♣, Pass ]");
+        let logs = CAPTURED_LOGS.with(RefCell::take);
+        assert!(logs.contains(&expected), "{logs:?}");
+    }
+}
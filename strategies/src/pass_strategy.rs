@@ -0,0 +1,71 @@
+use types::{game_state::PublicInfo, Action, PlayerState, Strategy};
+
+use crate::DefaultStrategy;
+
+/// Passes whenever it may, and otherwise plays like `DefaultStrategy`. Useful for exercising
+/// pile clears and all-pass rounds.
+#[derive(Debug, Default)]
+pub struct PassStrategy {
+    fallback: DefaultStrategy,
+}
+
+impl Strategy for PassStrategy {
+    fn name(&self) -> &'static str {
+        "pass"
+    }
+
+    fn select_action(
+        &mut self,
+        private_info: &PlayerState,
+        public_info: &PublicInfo,
+        available_actions: &[Action],
+    ) -> Action {
+        if available_actions.contains(&Action::Pass) {
+            return Action::Pass;
+        }
+        self.fallback
+            .select_action(private_info, public_info, available_actions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use types::{GameConfig, GameState};
+
+    use super::*;
+
+    #[test]
+    fn passing_players_clear_the_pile_every_round_and_finish() {
+        let players = (0..3)
+            .map(|idx| {
+                (
+                    format!("p{idx}"),
+                    Box::new(PassStrategy::default()) as Box<dyn Strategy>,
+                )
+            })
+            .collect();
+        let mut game_state = GameState::new_with_seed(players, GameConfig::default(), 0).unwrap();
+        game_state.run_pregame();
+        while game_state.still_playing() {
+            assert!(game_state.turns_taken() < 1000, "game never ended");
+            let available_actions = game_state.permitted_actions();
+            let public_info = game_state.public_info();
+            let player = game_state.current_player_mut();
+            let action =
+                player
+                    .strategy
+                    .select_action(&player.state, &public_info, &available_actions);
+            game_state.perform_ingame_action(&action);
+        }
+        // everyone passes on every lead, so each round is a single play
+        let rounds = game_state.rounds();
+        assert!(rounds.len() > 10);
+        assert!(rounds.iter().all(|round| {
+            round
+                .iter()
+                .filter(|event| event.action.plays_cards())
+                .count()
+                == 1
+        }));
+    }
+}
@@ -1,14 +1,25 @@
 use std::fmt::Display;
 
 use itertools::Itertools;
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::{card::Card, card_play::CardPlay};
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum Action {
-    SendCard { to: Uuid, card: Card },
-    PlayCards { card_play: CardPlay },
+    SendCard {
+        to: Uuid,
+        card: Card,
+    },
+    PlayCards {
+        card_play: CardPlay,
+    },
+    /// Two sets of the same rank and size played at once, which clears the pile
+    PlayThenComplete {
+        first: CardPlay,
+        complete: CardPlay,
+    },
     Pass,
 }
 
@@ -20,11 +31,31 @@ impl Display for Action {
             Action::PlayCards { card_play } => {
                 format!("Play {}", card_play.to_vec().iter().join(","))
             }
+            Action::PlayThenComplete { first, complete } => format!(
+                "Play {} then {}",
+                first.to_vec().iter().join(","),
+                complete.to_vec().iter().join(",")
+            ),
         };
         write!(f, "{}", string)
     }
 }
 
+impl Action {
+    /// Sets of cards this action puts on the pile, in the order they're played
+    pub fn card_plays(&self) -> Vec<CardPlay> {
+        match *self {
+            Action::PlayCards { card_play } => vec![card_play],
+            Action::PlayThenComplete { first, complete } => vec![first, complete],
+            Action::SendCard { .. } | Action::Pass => vec![],
+        }
+    }
+
+    pub fn plays_cards(&self) -> bool {
+        !self.card_plays().is_empty()
+    }
+}
+
 impl From<&CardPlay> for Action {
     fn from(&card_play: &CardPlay) -> Self {
         Action::PlayCards { card_play }
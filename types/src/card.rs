@@ -1,6 +1,7 @@
 use std::{cmp::Ordering, fmt::Display};
 
 use deckofcards::{Card as DOCCard, Rank, Suit};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Card {
@@ -51,16 +52,7 @@ impl Card {
 
 impl PartialOrd for Card {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        if self.card.rank == other.card.rank {
-            return Some(Ordering::Equal);
-        }
-        if self.card.rank == Rank::Two {
-            return Some(Ordering::Greater);
-        }
-        if other.card.rank == Rank::Two {
-            return Some(Ordering::Less);
-        }
-        Some(self.card.rank.ordinal().cmp(&other.card.rank.ordinal()))
+        Some(self.cmp(other))
     }
 }
 
@@ -84,3 +76,19 @@ impl From<DOCCard> for Card {
         Self { card }
     }
 }
+
+// as short strings like "3C", "TH"
+impl Serialize for Card {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.card.to_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Card {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let card_str = String::deserialize(deserializer)?;
+        DOCCard::from_str(&card_str)
+            .map(Card::from)
+            .map_err(de::Error::custom)
+    }
+}
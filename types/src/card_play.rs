@@ -2,10 +2,12 @@ use std::{cmp::Ordering, fmt::Display};
 
 use deckofcards::Rank;
 use itertools::Itertools;
+use serde::{Deserialize, Serialize};
 
 use crate::card::Card;
 
-#[derive(PartialEq, Eq, Copy, Clone, Debug, Ord)]
+#[allow(clippy::derive_ord_xor_partial_ord)]
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Ord, Serialize, Deserialize)]
 pub enum CardPlay {
     Single(Card),
     Pair(Card, Card),
@@ -13,6 +15,30 @@ pub enum CardPlay {
     Quad(Card, Card, Card, Card),
 }
 
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CardPlayError {
+    WrongNumberOfCards(usize),
+    MismatchedRanks(Vec<Card>),
+}
+
+impl Display for CardPlayError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CardPlayError::WrongNumberOfCards(num_cards) => write!(
+                f,
+                "Attempted to make a CardPlay from {num_cards} cards, must be 1 to 4"
+            ),
+            CardPlayError::MismatchedRanks(cards) => write!(
+                f,
+                "Attempted to make a CardPlay from cards of different ranks: {}",
+                cards.iter().join(", ")
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CardPlayError {}
+
 impl Display for CardPlay {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -26,6 +52,8 @@ impl Display for CardPlay {
     }
 }
 
+// plays of different sizes are incomparable, so this intentionally differs from the derived `Ord`
+#[allow(clippy::non_canonical_partial_ord_impl)]
 impl PartialOrd for CardPlay {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         if std::mem::discriminant(self) != std::mem::discriminant(other) {
@@ -49,17 +77,24 @@ impl PartialOrd for CardPlay {
 
 impl CardPlay {
     pub fn from_cards(cards: &[&Card]) -> CardPlay {
+        Self::try_from_cards(cards).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    pub fn try_from_cards(cards: &[&Card]) -> Result<CardPlay, CardPlayError> {
+        if cards.iter().map(|card| card.rank()).unique().count() > 1 {
+            return Err(CardPlayError::MismatchedRanks(
+                cards.iter().map(|&&card| card).collect(),
+            ));
+        }
         match *cards {
-            [card] => CardPlay::Single(*card),
-            [card1, card2] => CardPlay::Pair(*card1, *card2),
-            [card1, card2, card3] => CardPlay::Triple(*card1, *card2, *card3),
-            [card1, card2, card3, card4] => CardPlay::Quad(*card1, *card2, *card3, *card4),
-            _ => panic!(
-                "Attempted to make a CardPlay from more than four cards: {:?}",
-                cards
-            ),
+            [card] => Ok(CardPlay::Single(*card)),
+            [card1, card2] => Ok(CardPlay::Pair(*card1, *card2)),
+            [card1, card2, card3] => Ok(CardPlay::Triple(*card1, *card2, *card3)),
+            [card1, card2, card3, card4] => Ok(CardPlay::Quad(*card1, *card2, *card3, *card4)),
+            _ => Err(CardPlayError::WrongNumberOfCards(cards.len())),
         }
     }
+
     pub fn to_vec(self: &CardPlay) -> Vec<Card> {
         match self {
             CardPlay::Single(card) => vec![*card],
@@ -96,3 +131,33 @@ impl CardPlay {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use deckofcards::Suit;
+
+    use super::*;
+
+    #[test]
+    fn try_from_cards_checks_size_and_rank() {
+        let five_clubs = Card::new(Rank::Five, Suit::Clubs);
+        let five_spades = Card::new(Rank::Five, Suit::Spades);
+        let six_clubs = Card::new(Rank::Six, Suit::Clubs);
+        assert_eq!(
+            CardPlay::try_from_cards(&[&five_clubs, &five_spades]),
+            Ok(CardPlay::Pair(five_clubs, five_spades))
+        );
+        assert_eq!(
+            CardPlay::try_from_cards(&[]),
+            Err(CardPlayError::WrongNumberOfCards(0))
+        );
+        assert_eq!(
+            CardPlay::try_from_cards(&[&five_clubs; 5]),
+            Err(CardPlayError::WrongNumberOfCards(5))
+        );
+        assert_eq!(
+            CardPlay::try_from_cards(&[&five_clubs, &six_clubs]),
+            Err(CardPlayError::MismatchedRanks(vec![five_clubs, six_clubs]))
+        );
+    }
+}
@@ -0,0 +1,136 @@
+use deckofcards::{Rank, Suit};
+use serde::{Deserialize, Serialize};
+
+use crate::Card;
+
+/// Decides who leads the first trick of a game, and with which card
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StartingRule {
+    /// Whoever holds the first of these cards that was dealt leads with it
+    SpecificCards(Vec<Card>),
+    /// Whoever holds the lowest card dealt leads with it, ties going to clubs, spades, hearts,
+    /// then diamonds
+    LowestCard,
+}
+
+/// How a shuffled deck is split into hands
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DealMode {
+    #[default]
+    Random,
+    /// Each rank goes out in sets to whichever hand is worth least so far, so hand values even
+    /// out while pairs and bigger sets still turn up, and nobody gets more than their share of
+    /// the 2s
+    Balanced,
+}
+
+/// How players still holding cards are placed, which only matters when standings are taken
+/// before the game plays out, e.g. when it's cut short
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LastPlaceRule {
+    /// Ranked by seat, as if they'd gone out in turn order
+    #[default]
+    Ordered,
+    /// All share the last place, with roles still handed out by seat, so only one is Asshole
+    Shared,
+    /// All share the last place and all become Asshole
+    SharedAssholes,
+}
+
+impl Default for StartingRule {
+    fn default() -> Self {
+        StartingRule::SpecificCards(vec![
+            Card::new(Rank::Three, Suit::Clubs),
+            Card::new(Rank::Three, Suit::Spades),
+            Card::new(Rank::Three, Suit::Hearts),
+            Card::new(Rank::Three, Suit::Diamonds),
+            Card::new(Rank::Four, Suit::Clubs),
+        ])
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GameConfig {
+    /// Cards swapped between the Asshole and the President before each game
+    pub president_swap: usize,
+    /// Cards swapped between the ViceAsshole and the VicePresident before each game
+    pub vp_swap: usize,
+    /// Cards swapped between the ViceAsshole and the Secretary before each game, if there is a
+    /// Secretary (5 or more players)
+    pub secretary_swap: usize,
+    /// Playing any set of 2s clears the pile and the same player leads again
+    pub twos_clear_pile: bool,
+    /// Passing sits a player out until the pile is cleared
+    pub sticky_passes: bool,
+    /// Playing four of a kind reverses card rankings until the pile clears
+    pub quads_reverse: bool,
+    /// A set of the top card's size followed at once by another of the same rank clears the
+    /// pile, even if it doesn't beat the top card
+    pub double_down_clears: bool,
+    /// The first play of a game is the starting card alone, even if its holder has more of that
+    /// rank
+    pub open_with_single: bool,
+    /// Players may play part of a set, e.g. a single from a pair. When false, every play of a
+    /// rank uses all the cards of that rank in hand
+    pub allow_breaking_sets: bool,
+    /// The President and VicePresident can't send the starting card back in the swap, e.g. the
+    /// 3♣ they were just given. On by default
+    pub asshole_keeps_starting_card: bool,
+    pub starting_rule: StartingRule,
+    pub deal_mode: DealMode,
+    pub last_place_rule: LastPlaceRule,
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        Self {
+            president_swap: 2,
+            vp_swap: 1,
+            secretary_swap: 0,
+            twos_clear_pile: false,
+            sticky_passes: false,
+            quads_reverse: false,
+            double_down_clears: false,
+            open_with_single: false,
+            allow_breaking_sets: true,
+            asshole_keeps_starting_card: true,
+            starting_rule: StartingRule::default(),
+            deal_mode: DealMode::default(),
+            last_place_rule: LastPlaceRule::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_through_json() {
+        let config = GameConfig {
+            secretary_swap: 1,
+            quads_reverse: true,
+            starting_rule: StartingRule::SpecificCards(vec![Card::new(Rank::Four, Suit::Hearts)]),
+            deal_mode: DealMode::Balanced,
+            ..GameConfig::default()
+        };
+        let json = serde_json::to_string(&config).unwrap();
+        assert!(json.contains(r#""starting_rule":{"SpecificCards":["4H"]}"#));
+        assert_eq!(serde_json::from_str::<GameConfig>(&json).unwrap(), config);
+    }
+
+    #[test]
+    fn missing_rules_take_their_default() {
+        let config: GameConfig =
+            serde_json::from_str(r#"{"vp_swap": 0, "starting_rule": "LowestCard"}"#).unwrap();
+        assert_eq!(
+            config,
+            GameConfig {
+                vp_swap: 0,
+                starting_rule: StartingRule::LowestCard,
+                ..GameConfig::default()
+            }
+        );
+    }
+}
@@ -1,107 +1,651 @@
-use std::{
-    collections::{HashSet, VecDeque},
-    fmt::Display,
-};
+use std::{cell::RefCell, collections::VecDeque, fmt::Display};
 
-use deckofcards::{Deck, Rank, Suit};
+use deckofcards::{Rank, Suit};
 use itertools::Itertools;
 use log;
 use rand::prelude::*;
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::{
-    action::Action, card_play::CardPlay, hand::Hand, player::Player, Card, PlayerState,
-    PublicPlayerState, Role, Strategy,
+    action::Action,
+    card_play::CardPlay,
+    game_summary::{GameSummary, PlayerSummary},
+    hand::Hand,
+    player::Player,
+    player_view::PlayerView,
+    sim_game_state::{RolloutStrategy, SimGameState},
+    Card, DealMode, GameConfig, LastPlaceRule, PlayerState, PublicPlayerState, Role, StartingRule,
+    Strategy,
 };
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Event {
     pub player_id: Uuid,
     pub action: Action,
 }
 
+/// `(id, name, hand, strategy)` for `GameState::new_with_hands`
+pub type SeatedPlayer = (Uuid, String, Vec<Card>, Box<dyn Strategy>);
+
+// singles, pairs, triples and quads
+type CardPlaysBySize = [Vec<CardPlay>; 4];
+
 #[derive(Debug)]
 pub struct GameState {
     pub table: VecDeque<Player>,
     pub top_card: Option<CardPlay>,
     pub history: Vec<Event>,
+    pub config: GameConfig,
+    // lower cards beat higher ones, until the next quad or the pile clears
+    pub reversed: bool,
+    rng: StdRng,
+    deck_seed: String,
+    // history lengths at which the pile was cleared
+    pile_clears: Vec<usize>,
+    // players sitting out until the pile clears, when passes are sticky
+    passed_this_round: Vec<Uuid>,
+    // cards left over after dealing equal hands
+    deck_remaining: Vec<Card>,
+    // ingame actions taken this game
+    turns: usize,
+    // the last hand permitted_actions was asked about, and its card plays
+    card_play_cache: RefCell<Option<(Vec<Card>, CardPlaysBySize)>>,
+    // hands were chosen with new_with_hands, so the opening lead is free
+    custom_deal: bool,
+    // card swaps are done and someone has been given the lead
+    pregame_done: bool,
 }
 
-#[derive(Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Phase {
+    /// Cards haven't been swapped yet, or are being swapped, and nobody has played
+    Pregame,
+    Ingame,
+    /// At most one player has cards left
+    Finished,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
 pub struct PublicInfo {
+    /// The player being asked to act, which in pregame is whoever is sending cards
+    pub current_player_id: Uuid,
     pub top_card: Option<CardPlay>,
+    /// Lower plays beat the top card, after a quad under `quads_reverse`
+    pub reversed: bool,
     pub history: Vec<Event>,
     pub public_table: Vec<PublicPlayerState>,
+    /// Players who have gone out this game, first finisher first
+    pub finished: Vec<Uuid>,
+    /// Players sitting out until the pile clears; only ever filled with sticky passes
+    pub passed_this_round: Vec<Uuid>,
+    pub phase: Phase,
+}
+
+impl PublicInfo {
+    /// Cards no opponent can be holding: everything played so far plus `my_hand`, lowest first
+    pub fn definitely_out_of_play(&self, my_hand: &[Card]) -> Vec<Card> {
+        self.history
+            .iter()
+            .flat_map(|event| event.action.card_plays())
+            .flat_map(|card_play| card_play.to_vec())
+            .chain(my_hand.iter().copied())
+            .unique()
+            .sorted()
+            .collect()
+    }
+
+    /// Upper bound on how many opponents could answer `top`: if some set in `known_unseen` beats
+    /// it, any opponent holding enough cards might have that set. Not a probability.
+    pub fn opponents_who_can_beat(&self, top: CardPlay, known_unseen: &[Card]) -> usize {
+        let beatable = known_unseen
+            .to_vec()
+            .grouped_by_rank()
+            .iter()
+            .filter(|(_, cards)| cards.len() >= top.size())
+            .any(|(_, cards)| {
+                CardPlay::from_cards(&cards.iter().take(top.size()).collect_vec()) > top
+            });
+        if !beatable {
+            return 0;
+        }
+        self.public_table
+            .iter()
+            .filter(|player| player.id != self.current_player_id && player.hand_size >= top.size())
+            .count()
+    }
+}
+
+// one line per player with what they've played, then the top card
+impl PublicInfo {
+    /// One line per player, starting from the current player, with their role, hand size and
+    /// everything they've played
+    pub fn player_lines(&self) -> impl Iterator<Item = String> + '_ {
+        self.public_table.iter().map(|player| {
+            let card_plays = self
+                .history
+                .iter()
+                .filter(|event| event.player_id == player.id)
+                .flat_map(|event| event.action.card_plays())
+                .sorted()
+                .join(", ");
+            format!(
+                "{} ({}) has {} cards left and has played: [ {card_plays} ]",
+                player.name,
+                player
+                    .role
+                    .map(|r| r.to_string())
+                    .unwrap_or("None".to_string()),
+                player.hand_size,
+            )
+        })
+    }
+}
+
+impl Display for PublicInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for line in self.player_lines() {
+            writeln!(f, "{line}")?;
+        }
+        let top_card_str = self
+            .top_card
+            .map(|cp| cp.to_string())
+            .unwrap_or("None".to_string());
+        write!(f, "Top card is: {top_card_str}")
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ReplayError {
+    /// The game was already over when this turn was recorded
+    GameOver {
+        turn: usize,
+        event: Event,
+    },
+    OutOfTurn {
+        turn: usize,
+        event: Event,
+        expected_player_id: Uuid,
+    },
+    NotPermitted {
+        turn: usize,
+        event: Event,
+    },
+    /// The game to replay into couldn't be set up
+    Setup(GameError),
+}
+
+impl From<GameError> for ReplayError {
+    fn from(err: GameError) -> Self {
+        ReplayError::Setup(err)
+    }
+}
+
+impl Display for ReplayError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReplayError::GameOver { turn, event } => {
+                write!(f, "Turn {turn}: {} after the game ended", event.action)
+            }
+            ReplayError::OutOfTurn {
+                turn,
+                event,
+                expected_player_id,
+            } => write!(
+                f,
+                "Turn {turn}: {} did {} but it was {}'s turn",
+                event.player_id, event.action, expected_player_id
+            ),
+            ReplayError::NotPermitted { turn, event } => write!(
+                f,
+                "Turn {turn}: {} did {}, which was not permitted",
+                event.player_id, event.action
+            ),
+            ReplayError::Setup(err) => write!(f, "Unable to set up the replay: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ActionError {
+    /// This card from the play isn't in the current player's hand
+    NotInHand(Card),
+    NotGreaterThanTop {
+        card_play: CardPlay,
+        top_card: Option<CardPlay>,
+    },
+    SendDuringGame,
+    /// The rule is off, there's no top card, it would be the game's first play, or the sets
+    /// differ from each other or the top card in rank or size
+    InvalidDoubleDown {
+        first: CardPlay,
+        complete: CardPlay,
+    },
+    /// Breaks another rule, e.g. passing on an empty pile, opening without the starting card,
+    /// or breaking a set when that isn't allowed
+    NotPermitted(Action),
+}
+
+impl Display for ActionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ActionError::NotInHand(card) => {
+                write!(f, "Attempted to play a card {card} that wasn't in the hand")
+            }
+            ActionError::NotGreaterThanTop {
+                card_play,
+                top_card,
+            } => write!(
+                f,
+                "Attempted to play {card_play}, which doesn't beat the top card {}",
+                top_card.map_or_else(|| "None".to_string(), |cp| cp.to_string())
+            ),
+            ActionError::SendDuringGame => {
+                write!(f, "Attempted to send a card in the middle of the game")
+            }
+            ActionError::InvalidDoubleDown { first, complete } => {
+                write!(
+                    f,
+                    "Attempted to play {first} then {complete}, which isn't a double down"
+                )
+            }
+            ActionError::NotPermitted(action) => {
+                write!(f, "Attempted \"{action}\", which isn't permitted right now")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ActionError {}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GameError {
+    TooFewPlayers(usize),
+    /// More players than cards, so someone would be dealt an empty hand
+    TooManyPlayers(usize),
+    /// A supplied deck leaves every starting card undealt, so nobody could lead
+    StartingCardsUndealt,
+    /// A swap in the config needs more cards than each player is dealt, counting the starting
+    /// card as unsendable if the Asshole keeps it
+    SwapLargerThanHand {
+        num_cards: usize,
+        hand_size: usize,
+    },
+    DuplicateCard(Card),
+    /// A supplied deck didn't have exactly one of each card
+    WrongDeckSize(usize),
+}
+
+impl Display for GameError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GameError::TooFewPlayers(num_players) => {
+                write!(f, "Need at least {MIN_PLAYERS} players, got {num_players}")
+            }
+            GameError::TooManyPlayers(num_players) => write!(
+                f,
+                "Can deal to at most {} players, got {num_players}",
+                Card::all_cards().len()
+            ),
+            GameError::StartingCardsUndealt => {
+                write!(f, "Every starting card was left undealt")
+            }
+            GameError::SwapLargerThanHand {
+                num_cards,
+                hand_size,
+            } => write!(
+                f,
+                "Hands of {hand_size} cards are too small to swap {num_cards}"
+            ),
+            GameError::DuplicateCard(card) => write!(f, "{card} was dealt more than once"),
+            GameError::WrongDeckSize(size) => write!(
+                f,
+                "Expected a deck of {} cards, got {size}",
+                Card::all_cards().len()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for GameError {}
+
+const MIN_PLAYERS: usize = 2;
+
+// every game dealt from a full deck to this table can be played under `config`: everyone gets
+// cards and each swap fits in a hand. `deal` makes sure someone holds a starting card.
+fn check_num_players(num_players: usize, config: &GameConfig) -> Result<(), GameError> {
+    let deck_size = Card::all_cards().len();
+    if num_players < MIN_PLAYERS {
+        return Err(GameError::TooFewPlayers(num_players));
+    }
+    if num_players > deck_size {
+        return Err(GameError::TooManyPlayers(num_players));
+    }
+    let hand_size = deck_size / num_players;
+    // there's only a Secretary with 5 or more players
+    let secretary_swap = if num_players >= 5 {
+        config.secretary_swap
+    } else {
+        0
+    };
+    let num_cards = config
+        .president_swap
+        .max(config.vp_swap)
+        .max(secretary_swap);
+    // the President and VicePresident can't send the starting card back, so need a card to spare
+    let num_spare = usize::from(config.asshole_keeps_starting_card && num_cards > 0);
+    if num_cards + num_spare > hand_size {
+        return Err(GameError::SwapLargerThanHand {
+            num_cards,
+            hand_size,
+        });
+    }
+    Ok(())
 }
 
 impl GameState {
-    pub fn new(player_inputs: Vec<(String, Box<dyn Strategy>)>) -> Self {
+    pub fn new(player_inputs: Vec<(String, Box<dyn Strategy>)>) -> Result<Self, GameError> {
+        Self::new_with_config(player_inputs, GameConfig::default())
+    }
+
+    pub fn new_with_config(
+        player_inputs: Vec<(String, Box<dyn Strategy>)>,
+        config: GameConfig,
+    ) -> Result<Self, GameError> {
+        Self::new_with_seed(player_inputs, config, thread_rng().gen())
+    }
+
+    pub fn new_with_seed(
+        player_inputs: Vec<(String, Box<dyn Strategy>)>,
+        config: GameConfig,
+        seed: u64,
+    ) -> Result<Self, GameError> {
+        let rng = StdRng::seed_from_u64(seed);
+        Self::new_with_rng_and_seed(player_inputs, config, rng, seed.to_string())
+    }
+
+    /// Deals and seats players using `rng`, which is kept for any later randomness in the
+    /// session, so a seeded rng gives a reproducible game. The seed behind `rng` is unknown, so
+    /// `deck_seed` is a fresh UUID instead.
+    pub fn new_with_rng(
+        player_inputs: Vec<(String, Box<dyn Strategy>)>,
+        config: GameConfig,
+        rng: StdRng,
+    ) -> Result<Self, GameError> {
+        let deck_seed = Uuid::new_v4().to_string();
+        Self::new_with_rng_and_seed(player_inputs, config, rng, deck_seed)
+    }
+
+    fn new_with_rng_and_seed(
+        player_inputs: Vec<(String, Box<dyn Strategy>)>,
+        config: GameConfig,
+        mut rng: StdRng,
+        deck_seed: String,
+    ) -> Result<Self, GameError> {
         let num_players = player_inputs.len();
-        let mut deck = Deck::new();
-        deck.reset_shuffle();
-        let hand_size = deck.count() / num_players;
-        log::info!("Num players: {num_players:?}, hand size: {hand_size:?}");
+        check_num_players(num_players, &config)?;
+        let (hands, deck_remaining) = deal(&config, num_players, &mut rng);
+        let hand_size = hands.first().map_or(0, Vec::len);
+        log::info!(
+            "Num players: {num_players:?}, hand size: {hand_size:?}, deck seed: {deck_seed}"
+        );
         let mut players: Vec<_> = player_inputs
             .into_iter()
-            .map(|(name, strat)| {
-                let cards: Vec<_> = deck.deal(hand_size).into_iter().map_into().collect();
-                assert_eq!(cards.len(), hand_size);
+            .zip(hands)
+            .map(|((name, strat), cards)| {
+                // ids come from the rng too, so a seeded game can be replayed event for event
+                let mut state = PlayerState::new(name, cards, None);
+                state.id = uuid::Builder::from_random_bytes(rng.gen()).into_uuid();
                 Player {
-                    state: PlayerState::new(name, cards, None),
+                    state,
                     strategy: strat,
                 }
             })
             .collect();
 
-        players.shuffle(&mut thread_rng());
-        let table = VecDeque::from(players);
+        players.shuffle(&mut rng);
+        let mut game_state = Self::from_table(VecDeque::from(players), config, rng, deck_seed);
+        game_state.deck_remaining = deck_remaining;
+        Ok(game_state)
+    }
+
+    /// Deals `deck` in the given order, without shuffling, and seats players in the order given,
+    /// for fully scripted games. Later games in the session are shuffled as usual. Someone must
+    /// be dealt one of the default starting cards.
+    pub fn new_with_deck(
+        player_inputs: Vec<(String, Box<dyn Strategy>)>,
+        deck: Vec<Card>,
+    ) -> Result<Self, GameError> {
+        check_num_players(player_inputs.len(), &GameConfig::default())?;
+        let expected = Card::all_cards().len();
+        if deck.len() != expected {
+            return Err(GameError::WrongDeckSize(deck.len()));
+        }
+        if let Some(&card) = deck.iter().duplicates().next() {
+            return Err(GameError::DuplicateCard(card));
+        }
+        let hand_size = deck.len() / player_inputs.len();
+        let deck_remaining = deck[hand_size * player_inputs.len()..].to_vec();
+        if let StartingRule::SpecificCards(starting_cards) = &GameConfig::default().starting_rule {
+            if starting_cards
+                .iter()
+                .all(|card| deck_remaining.contains(card))
+            {
+                return Err(GameError::StartingCardsUndealt);
+            }
+        }
+        let table = player_inputs
+            .into_iter()
+            .zip(deck.chunks(hand_size))
+            .map(|((name, strategy), cards)| Player {
+                state: PlayerState::new(name, cards.to_vec(), None),
+                strategy,
+            })
+            .collect();
+        let mut game_state = Self::from_table(
+            table,
+            GameConfig::default(),
+            StdRng::from_entropy(),
+            Uuid::new_v4().to_string(),
+        );
+        game_state.deck_remaining = deck_remaining;
+        Ok(game_state)
+    }
+
+    /// Seats players in the given order with exactly the given hands, the first player to act,
+    /// and `top_card` already on the pile, e.g. to set up an endgame. There's no starting card,
+    /// so the first play is unrestricted. There must be at least two players, and no card may be
+    /// in more than one hand.
+    pub fn new_with_hands(
+        players: Vec<SeatedPlayer>,
+        top_card: Option<CardPlay>,
+    ) -> Result<Self, GameError> {
+        // the deal is given, so the full-deck limits in check_num_players don't apply
+        if players.len() < MIN_PLAYERS {
+            return Err(GameError::TooFewPlayers(players.len()));
+        }
+        let all_cards = players
+            .iter()
+            .flat_map(|(_, _, hand, _)| hand)
+            .collect_vec();
+        if let Some(&&card) = all_cards.iter().duplicates().next() {
+            return Err(GameError::DuplicateCard(card));
+        }
+        let table = players
+            .into_iter()
+            .map(|(id, name, hand, strategy)| {
+                let mut state = PlayerState::new(name, hand, None);
+                state.id = id;
+                Player { state, strategy }
+            })
+            .collect();
+
+        let mut game_state = Self::from_table(
+            table,
+            GameConfig::default(),
+            StdRng::from_entropy(),
+            Uuid::new_v4().to_string(),
+        );
+        game_state.top_card = top_card;
+        game_state.custom_deal = true;
+        Ok(game_state)
+    }
 
+    fn from_table(
+        table: VecDeque<Player>,
+        config: GameConfig,
+        rng: StdRng,
+        deck_seed: String,
+    ) -> Self {
         Self {
             table,
             top_card: None,
             history: Vec::new(),
+            config,
+            reversed: false,
+            rng,
+            deck_seed,
+            pile_clears: Vec::new(),
+            passed_this_round: Vec::new(),
+            deck_remaining: Vec::new(),
+            turns: 0,
+            card_play_cache: RefCell::new(None),
+            custom_deal: false,
+            pregame_done: false,
+        }
+    }
+
+    /// Seed the session's rng was built from: the decimal `u64` for seeded games
+    pub fn deck_seed(&self) -> &str {
+        &self.deck_seed
+    }
+
+    /// Ingame actions taken so far this game, passes included
+    pub fn turns_taken(&self) -> usize {
+        self.turns
+    }
+
+    /// Cards that weren't dealt because they wouldn't split evenly between the players
+    pub fn deck_remaining(&self) -> &[Card] {
+        &self.deck_remaining
+    }
+
+    /// Strategy-free copy of the game so far, for rolling out hypothetical lines of play
+    pub fn fork(&self) -> SimGameState {
+        SimGameState::new(self.copy_with_strategies(|| Box::new(RolloutStrategy)))
+    }
+
+    // everything but the strategies, which can't be cloned
+    pub(crate) fn copy_with_strategies(
+        &self,
+        mut make_strategy: impl FnMut() -> Box<dyn Strategy>,
+    ) -> GameState {
+        let table = self
+            .table
+            .iter()
+            .map(|player| Player {
+                state: player.state.clone(),
+                strategy: make_strategy(),
+            })
+            .collect();
+        GameState {
+            table,
+            top_card: self.top_card,
+            history: self.history.clone(),
+            config: self.config.clone(),
+            reversed: self.reversed,
+            rng: self.rng.clone(),
+            deck_seed: self.deck_seed.clone(),
+            pile_clears: self.pile_clears.clone(),
+            passed_this_round: self.passed_this_round.clone(),
+            deck_remaining: self.deck_remaining.clone(),
+            turns: self.turns,
+            card_play_cache: self.card_play_cache.clone(),
+            custom_deal: self.custom_deal,
+            pregame_done: self.pregame_done,
         }
     }
 
     pub fn public_info(&self) -> PublicInfo {
         PublicInfo {
+            current_player_id: self.current_player().state.id,
             top_card: self.top_card,
+            reversed: self.reversed,
             history: self.history.clone(),
             public_table: self.table.iter().map(|p| (&p.state).into()).collect(),
+            finished: self.finished_players(),
+            passed_this_round: self.passed_this_round.clone(),
+            phase: self.current_phase(),
+        }
+    }
+
+    /// What player `id` can see: their own full state plus everything public, e.g. to send to a
+    /// remote client. `None` if they aren't at the table.
+    pub fn view_for(&self, id: Uuid) -> Option<PlayerView> {
+        let player = self.get_player(id)?;
+        Some(PlayerView {
+            player: player.state.clone(),
+            public_info: self.public_info(),
+        })
+    }
+
+    // a finished player went out on their last card play
+    fn finished_players(&self) -> Vec<Uuid> {
+        self.table
+            .iter()
+            .filter(|player| player.state.is_finished())
+            .map(|player| player.state.id)
+            .sorted_by_key(|&id| {
+                self.history
+                    .iter()
+                    .rposition(|event| event.player_id == id && event.action.plays_cards())
+            })
+            .collect()
+    }
+
+    pub fn summary(&self) -> GameSummary {
+        GameSummary {
+            num_players: self.table.len(),
+            top_card: self.top_card,
+            turns_played: self.history.len(),
+            current_player: self.current_player().state.name.clone(),
+            reversed: self.reversed,
+            players: self
+                .table
+                .iter()
+                .map(|player| PlayerSummary {
+                    name: player.state.name.clone(),
+                    hand_size: player.state.current_hand.len(),
+                    role: player.state.role,
+                })
+                .collect(),
+        }
+    }
+
+    // singles, pairs, triples and quads in `hand`, reused while the same hand is asked about
+    fn card_plays_by_size(&self, hand: &Vec<Card>) -> CardPlaysBySize {
+        let mut cache = self.card_play_cache.borrow_mut();
+        match cache.as_ref() {
+            Some((cached_hand, card_plays)) if cached_hand == hand => card_plays.clone(),
+            _ => {
+                let card_plays = [hand.singles(), hand.pairs(), hand.triples(), hand.quads()];
+                *cache = Some((hand.clone(), card_plays.clone()));
+                card_plays
+            }
         }
     }
 
     pub fn permitted_actions(&self) -> Vec<Action> {
         let current_player = self.current_player();
         let hand = &current_player.state.current_hand;
+        let card_plays = self.card_plays_by_size(hand);
         let mut actions: Vec<Action> = match self.top_card {
-            None => [hand.singles(), hand.pairs(), hand.triples(), hand.quads()]
-                .concat()
-                .iter()
-                .map_into()
-                .collect(),
-            Some(CardPlay::Single(..)) => hand
-                .singles()
-                .iter()
-                .filter(|&&cp| Some(cp) > self.top_card)
-                .map_into()
-                .collect(),
-            Some(CardPlay::Pair(..)) => hand
-                .pairs()
-                .iter()
-                .filter(|&&cp| Some(cp) > self.top_card)
-                .map_into()
-                .collect(),
-            Some(CardPlay::Triple(..)) => hand
-                .triples()
-                .iter()
-                .filter(|&&cp| Some(cp) > self.top_card)
-                .map_into()
-                .collect(),
-            Some(CardPlay::Quad(..)) => hand
-                .quads()
+            None => card_plays.concat().iter().map_into().collect(),
+            Some(top_card) => card_plays[top_card.size() - 1]
                 .iter()
-                .filter(|&&cp| Some(cp) > self.top_card)
+                .filter(|&&cp| self.beats_top_card(cp))
                 .map_into()
                 .collect(),
         };
@@ -109,20 +653,21 @@ impl GameState {
         if self.top_card.is_some() {
             actions.push(Action::Pass);
         }
+        if let (true, Some(top_card)) = (self.config.double_down_clears, self.top_card) {
+            actions.extend(double_downs(hand, top_card.size()));
+        }
         // first card play must contain starting card
-        let is_first_cardplay = self
-            .history
-            .iter()
-            .all(|ev| !matches!(ev.action, Action::PlayCards { .. }));
-        if is_first_cardplay {
+        if self.is_first_cardplay() {
             let (_, starting_card) = self.starting_player_and_card();
             actions.retain(|action| match action {
                 Action::PlayCards { card_play } => {
-                    card_play.to_vec().iter().any(|&card| card == starting_card)
+                    card_play.to_vec().contains(&starting_card)
+                        && (!self.config.open_with_single || card_play.size() == 1)
                 }
                 _ => false,
             });
         }
+        actions.retain(|action| !self.breaks_set(action));
 
         log::debug!(
             "Available actions for {}: {actions:?}",
@@ -131,41 +676,280 @@ impl GameState {
         actions
     }
 
-    pub fn perform_ingame_action(&mut self, action: &Action) {
-        let player = self.current_player_mut();
-        let player_id = player.state.id;
+    /// Whether the current player may take `action` now, i.e. whether it's in
+    /// `permitted_actions()`, without building that list. Plays match regardless of card order.
+    pub fn is_action_legal(&self, action: &Action) -> bool {
+        let in_hand = |card_plays: &[CardPlay]| {
+            let mut remaining = self.current_player().state.clone();
+            card_plays.iter().all(|card_play| {
+                card_play.to_vec().iter().map(Card::rank).all_equal()
+                    && remaining.remove_card_play(card_play).is_ok()
+            })
+        };
+        if self.breaks_set(action) {
+            return false;
+        }
         match action {
-            Action::SendCard { .. } => {
-                panic!("Attempted to send a card in the middle of the game!");
+            Action::SendCard { .. } => false,
+            Action::Pass => self.top_card.is_some() && !self.is_first_cardplay(),
+            Action::PlayCards { card_play } => {
+                let includes_starting_card = !self.is_first_cardplay()
+                    || (card_play
+                        .to_vec()
+                        .contains(&self.starting_player_and_card().1)
+                        && (!self.config.open_with_single || card_play.size() == 1));
+                self.beats_top_card(*card_play) && includes_starting_card && in_hand(&[*card_play])
+            }
+            Action::PlayThenComplete { first, complete } => {
+                self.config.double_down_clears
+                    && !self.is_first_cardplay()
+                    && first.rank() == complete.rank()
+                    && self.top_card.is_some_and(|top| {
+                        top.size() == first.size() && top.size() == complete.size()
+                    })
+                    && in_hand(&[*first, *complete])
+            }
+        }
+    }
+
+    /// `permitted_actions` pruned to the lowest play of each size, plus everything that isn't a
+    /// plain card play, for strategies that want fewer options to search. This is a heuristic
+    /// that can drop good plays, e.g. a high single that wins the pile, not a rules change.
+    pub fn undominated_actions(&self) -> Vec<Action> {
+        let (plays, mut actions): (Vec<_>, Vec<_>) = self
+            .permitted_actions()
+            .into_iter()
+            .partition(|action| matches!(action, Action::PlayCards { .. }));
+        let lowest_plays = plays
+            .into_iter()
+            .filter_map(|action| match action {
+                Action::PlayCards { card_play } => Some(card_play),
+                _ => None,
+            })
+            .into_group_map_by(CardPlay::size)
+            .into_values()
+            .filter_map(|card_plays| card_plays.into_iter().min_by_key(CardPlay::value))
+            .sorted_by_key(CardPlay::size)
+            .map(|card_play| Action::PlayCards { card_play });
+        actions.splice(0..0, lowest_plays);
+        actions
+    }
+
+    /// Cheapest card play the current player may make, by size then value: the lowest lead on an
+    /// empty pile, otherwise the smallest play that beats the top card
+    pub fn minimal_beating_play(&self) -> Option<Action> {
+        self.permitted_actions()
+            .into_iter()
+            .filter(|action| matches!(action, Action::PlayCards { .. }))
+            .min_by_key(|action| {
+                action
+                    .card_plays()
+                    .first()
+                    .map(|card_play| (card_play.size(), card_play.value()))
+            })
+    }
+
+    // plays part of the current player's cards of a rank when the rules say not to. A forced
+    // single opening takes precedence.
+    fn breaks_set(&self, action: &Action) -> bool {
+        if self.config.allow_breaking_sets
+            || (self.config.open_with_single && self.is_first_cardplay())
+        {
+            return false;
+        }
+        let played = action
+            .card_plays()
+            .iter()
+            .flat_map(CardPlay::to_vec)
+            .collect_vec();
+        played.first().is_some_and(|card| {
+            let held = self
+                .current_player()
+                .state
+                .current_hand
+                .iter()
+                .filter(|held_card| held_card.rank() == card.rank())
+                .count();
+            played.len() < held
+        })
+    }
+
+    /// Same as `permitted_actions().len()`, but counted per rank without building the actions
+    pub fn legal_action_count(&self) -> usize {
+        let hand = &self.current_player().state.current_hand;
+        let starting_card = self
+            .is_first_cardplay()
+            .then(|| self.starting_player_and_card().1);
+        let sizes = match self.top_card {
+            None if starting_card.is_some() && self.config.open_with_single => 1..=1,
+            None => 1..=4,
+            Some(top_card) => top_card.size()..=top_card.size(),
+        };
+
+        let mut count = 0;
+        for (_, cards) in hand.grouped_by_rank() {
+            // (cards free to choose, cards every play of this rank must include)
+            let (num_free, num_required) = match starting_card {
+                None => (cards.len(), 0),
+                Some(card) if cards.contains(&card) => (cards.len() - 1, 1),
+                Some(_) => continue,
+            };
+            let whole_set_only = !self.config.allow_breaking_sets
+                && !(self.config.open_with_single && starting_card.is_some());
+            for size in sizes
+                .clone()
+                .filter(|&size| size <= cards.len() && (!whole_set_only || size == cards.len()))
+            {
+                let example_play = CardPlay::from_cards(&cards.iter().take(size).collect_vec());
+                if self.beats_top_card(example_play) {
+                    count += binomial(num_free, size - num_required);
+                }
+            }
+        }
+        if self.top_card.is_some() && starting_card.is_none() {
+            count += 1;
+        }
+        if let (true, Some(top_card)) = (self.config.double_down_clears, self.top_card) {
+            let size = top_card.size();
+            for (_, cards) in hand.grouped_by_rank() {
+                let fits = match self.config.allow_breaking_sets {
+                    true => cards.len() >= 2 * size,
+                    false => cards.len() == 2 * size,
+                };
+                if fits {
+                    // unordered pairs of disjoint sets
+                    count += binomial(cards.len(), size) * binomial(cards.len() - size, size) / 2;
+                }
             }
+        }
+        count
+    }
+
+    /// Applies `action` for the current player, panicking if it's illegal; see
+    /// `try_apply_action` for the non-panicking version
+    pub fn perform_ingame_action(&mut self, action: &Action) {
+        if let Err(err) = self.try_apply_action(action) {
+            panic!("{err}");
+        }
+    }
+
+    // why `action` isn't legal for the current player, as specifically as we can tell
+    fn check_action(&self, action: &Action) -> Result<(), ActionError> {
+        match action {
+            Action::SendCard { .. } => return Err(ActionError::SendDuringGame),
             Action::Pass => {}
             Action::PlayCards { card_play } => {
-                for card in &card_play.to_vec() {
-                    let removed = player.state.current_hand.remove_card(card);
-                    assert!(
-                        removed,
-                        "Attempted to play a card {:?} that wasn't in the hand!",
-                        card
-                    );
+                if !self.beats_top_card(*card_play) {
+                    return Err(ActionError::NotGreaterThanTop {
+                        card_play: *card_play,
+                        top_card: self.top_card,
+                    });
+                }
+                self.current_player()
+                    .state
+                    .clone()
+                    .remove_card_play(card_play)
+                    .map_err(ActionError::NotInHand)?;
+            }
+            Action::PlayThenComplete { first, complete } => {
+                let valid = self.config.double_down_clears
+                    && !self.is_first_cardplay()
+                    && first.rank() == complete.rank()
+                    && self.top_card.is_some_and(|top| {
+                        top.size() == first.size() && top.size() == complete.size()
+                    });
+                if !valid {
+                    return Err(ActionError::InvalidDoubleDown {
+                        first: *first,
+                        complete: *complete,
+                    });
+                }
+                let mut remaining = self.current_player().state.clone();
+                remaining
+                    .remove_card_play(first)
+                    .and_then(|_| remaining.remove_card_play(complete))
+                    .map_err(ActionError::NotInHand)?;
+            }
+        }
+        if !self.is_action_legal(action) {
+            return Err(ActionError::NotPermitted(*action));
+        }
+        Ok(())
+    }
+
+    /// Applies `action` for the current player, or leaves the game untouched and returns why it
+    /// isn't legal
+    pub fn try_apply_action(&mut self, action: &Action) -> Result<(), ActionError> {
+        self.check_action(action)?;
+        let player_id = self.current_player().state.id;
+        match action {
+            Action::SendCard { .. } => unreachable!("Rejected by check_action"),
+            Action::Pass => {
+                if self.config.sticky_passes {
+                    self.passed_this_round.push(player_id);
                 }
-                // check that played cards are greater than top card
-                assert!(Some(*card_play) > self.top_card);
+            }
+            Action::PlayCards { card_play } => {
+                self.current_player_mut()
+                    .state
+                    .remove_card_play(card_play)
+                    .expect("Checked to be in hand");
                 self.top_card = Some(*card_play);
+                if self.config.quads_reverse && matches!(card_play, CardPlay::Quad(..)) {
+                    self.reversed = !self.reversed;
+                }
+            }
+            Action::PlayThenComplete { first, complete } => {
+                let state = &mut self.current_player_mut().state;
+                state
+                    .remove_card_play(first)
+                    .and_then(|_| state.remove_card_play(complete))
+                    .expect("Checked to be in hand");
+                self.top_card = Some(*complete);
             }
         }
-        log::info!("{} did: {action}", self.current_player().state.name);
+        let current_player = self.current_player();
+        log::info!(
+            "{} ({}) did: {action}",
+            current_player.state.name,
+            current_player.strategy.name()
+        );
         // record event in history
         let event = Event {
             player_id,
             action: *action,
         };
         self.history.push(event);
+        self.turns += 1;
+
+        if self.clears_pile(action) {
+            self.clear_pile();
+            // same player leads again, unless that play took them out
+            if !self.current_player().state.is_finished() {
+                return Ok(());
+            }
+        }
 
         // also handles clearing the deck if necessary
         self.next_players_turn();
-        while self.current_player().state.current_hand.is_empty() {
+        while self.current_player().state.is_finished()
+            || self
+                .passed_this_round
+                .contains(&self.current_player().state.id)
+        {
             self.next_players_turn();
         }
+        Ok(())
+    }
+
+    fn clears_pile(&self, action: &Action) -> bool {
+        match action {
+            Action::PlayThenComplete { .. } => true,
+            Action::PlayCards { card_play } => {
+                self.config.twos_clear_pile && card_play.rank() == Rank::Two
+            }
+            _ => false,
+        }
     }
 
     fn next_players_turn(&mut self) {
@@ -173,13 +957,52 @@ impl GameState {
 
         // clear the deck if necessary
         if self.last_played_player().map(|p| &p.state) == Some(&self.current_player().state) {
-            self.top_card = None;
+            self.clear_pile();
+        }
+        // nobody played a preset top card, so it clears once everyone has passed on it
+        if self.last_played_player().is_none()
+            && self.top_card.is_some()
+            && self.history.len() >= self.active_player_count()
+        {
+            self.clear_pile();
+        }
+    }
+
+    fn is_first_cardplay(&self) -> bool {
+        !self.custom_deal && self.history.iter().all(|ev| !ev.action.plays_cards())
+    }
+
+    fn beats_top_card(&self, card_play: CardPlay) -> bool {
+        match self.top_card {
+            None => true,
+            Some(top_card) if self.reversed => card_play < top_card,
+            Some(top_card) => card_play > top_card,
+        }
+    }
+
+    fn clear_pile(&mut self) {
+        self.passed_this_round.clear();
+        self.reversed = false;
+        if self.top_card.take().is_some() {
+            self.pile_clears.push(self.history.len());
         }
     }
 
     pub fn run_pregame(&mut self) -> Vec<Event> {
-        let mut events = self.swap_cards_by_role(Role::Asshole, Role::President, 2);
-        events.append(&mut self.swap_cards_by_role(Role::ViceAsshole, Role::VicePresident, 1));
+        let mut events =
+            self.swap_cards_by_role(Role::Asshole, Role::President, self.config.president_swap);
+        events.append(&mut self.swap_cards_by_role(
+            Role::ViceAsshole,
+            Role::VicePresident,
+            self.config.vp_swap,
+        ));
+        if self.config.secretary_swap > 0 && self.get_role(Role::Secretary).is_some() {
+            events.append(&mut self.swap_cards_by_role(
+                Role::ViceAsshole,
+                Role::Secretary,
+                self.config.secretary_swap,
+            ));
+        }
         self.set_starting_player();
         events
     }
@@ -188,6 +1011,29 @@ impl GameState {
         self.table.iter().find(|p| p.state.id == id)
     }
 
+    pub fn hand_of(&self, id: Uuid) -> Option<&[Card]> {
+        self.get_player(id)
+            .map(|player| player.state.current_hand.as_slice())
+    }
+
+    pub fn hand_size_of(&self, id: Uuid) -> Option<usize> {
+        self.hand_of(id).map(<[Card]>::len)
+    }
+
+    /// Copies of every player's state in seating order, without the strategies, e.g. for
+    /// analysis after a game
+    pub fn player_states(&self) -> Vec<PlayerState> {
+        self.table
+            .iter()
+            .map(|player| player.state.clone())
+            .collect()
+    }
+
+    /// Like `player_states`, but consumes the game instead of cloning
+    pub fn into_player_states(self) -> Vec<PlayerState> {
+        self.table.into_iter().map(|player| player.state).collect()
+    }
+
     pub fn get_role(&self, role: Role) -> Option<&Player> {
         self.table.iter().find(|p| p.state.role == Some(role))
     }
@@ -215,42 +1061,52 @@ impl GameState {
     fn last_played_player(&self) -> Option<&Player> {
         self.history
             .iter()
-            .filter(|ev| matches!(ev.action, Action::PlayCards { .. }))
+            .filter(|ev| ev.action.plays_cards())
             .map(|ev| ev.player_id)
-            .last()
+            .next_back()
             .and_then(|player_id| self.get_player(player_id))
     }
 
     fn starting_player_and_card(&self) -> (Uuid, Card) {
-        let mut starter_id_and_card: Option<(Uuid, Card)> = None;
-        for three_card in [
-            Card::new(Rank::Three, Suit::Clubs),
-            Card::new(Rank::Three, Suit::Spades),
-            Card::new(Rank::Three, Suit::Hearts),
-            Card::new(Rank::Three, Suit::Diamonds),
-            Card::new(Rank::Four, Suit::Clubs),
-        ] {
-            if let Some(starter) = self
-                .table
+        let holder_of = |card: &Card| {
+            self.table
                 .iter()
-                .find(|player| player.state.current_hand.contains(&three_card))
-            {
-                starter_id_and_card = Some((starter.state.id, three_card));
-                break;
+                .find(|player| player.state.current_hand.contains(card))
+                .map(|player| (player.state.id, *card))
+        };
+        match &self.config.starting_rule {
+            StartingRule::SpecificCards(cards) => cards
+                .iter()
+                .find_map(holder_of)
+                .unwrap_or_else(|| panic!("Someone must have one of: {}", cards.iter().join(", "))),
+            StartingRule::LowestCard => {
+                let suit_order = [Suit::Clubs, Suit::Spades, Suit::Hearts, Suit::Diamonds];
+                self.table
+                    .iter()
+                    .flat_map(|player| player.state.current_hand.iter())
+                    .min_by_key(|card| {
+                        let suit_idx = suit_order.iter().position(|&suit| suit == card.suit());
+                        (card.value(), suit_idx)
+                    })
+                    .and_then(holder_of)
+                    .expect("Someone must have been dealt a card")
             }
         }
-        starter_id_and_card.expect("Someone must have one of: 3C, 3S, 3H, 3D, 4S")
     }
 
-    fn set_starting_player(&mut self) -> Card {
-        let (starter_id, card) = self.starting_player_and_card();
+    fn set_starting_player(&mut self) {
+        self.pregame_done = true;
+        // a custom deal is already seated with the leader first
+        if self.custom_deal {
+            return;
+        }
+        let (starter_id, _) = self.starting_player_and_card();
         let idx = self
             .table
             .iter()
             .position(|p| p.state.id == starter_id)
             .expect("Someone must have one of these cards");
         self.table.rotate_left(idx);
-        card
     }
 
     fn swap_cards_by_role(
@@ -259,7 +1115,11 @@ impl GameState {
         president_role: Role,
         num_cards: usize,
     ) -> Vec<Event> {
-        let public_info = self.public_info();
+        let mut public_info = self.public_info();
+        let kept_starting_card = self
+            .config
+            .asshole_keeps_starting_card
+            .then(|| self.starting_player_and_card().1);
 
         // generate events
         let events = match (
@@ -291,32 +1151,29 @@ impl GameState {
                     },
                 }));
 
-                let mut sent_cards: HashSet<Card> = HashSet::new();
+                // hand the president would keep, so strategies can see which cards are
+                // already on their way back and, e.g., avoid splitting a pair
+                let mut kept_state = president.state.clone();
+                public_info.current_player_id = president_id;
                 for _ in 0..num_cards {
-                    let available_actions: Vec<_> = president
-                        .state
+                    let available_actions: Vec<_> = kept_state
                         .current_hand
                         .iter()
-                        .filter_map(|&card| {
-                            if !sent_cards.contains(&card) {
-                                Some(Action::SendCard {
-                                    to: asshole_id,
-                                    card,
-                                })
-                            } else {
-                                None
-                            }
+                        .filter(|&&card| Some(card) != kept_starting_card)
+                        .map(|&card| Action::SendCard {
+                            to: asshole_id,
+                            card,
                         })
                         .collect();
 
                     // president/VP should send bottom cards strategically
                     let action = president.strategy.select_action(
-                        &president.state,
+                        &kept_state,
                         &public_info,
                         &available_actions,
                     );
                     if let Action::SendCard { card, .. } = action {
-                        sent_cards.insert(card);
+                        kept_state.current_hand.remove_card(&card);
                     }
                     events.push(Event {
                         player_id: president_id,
@@ -348,96 +1205,523 @@ impl GameState {
         events
     }
 
-    pub fn still_playing(&self) -> bool {
-        self.table
-            .iter()
-            .filter(|player| !player.state.current_hand.is_empty())
-            .count()
-            >= 2
-    }
+    /// Card sends the pregame swaps call for, in the order `run_pregame` makes them: the sender,
+    /// the receiver, and whether the sender has to give up their highest card
+    fn expected_sends(&self) -> Vec<(Uuid, Uuid, bool)> {
+        let mut swaps = vec![
+            (Role::Asshole, Role::President, self.config.president_swap),
+            (Role::ViceAsshole, Role::VicePresident, self.config.vp_swap),
+        ];
+        if self.config.secretary_swap > 0 && self.get_role(Role::Secretary).is_some() {
+            swaps.push((
+                Role::ViceAsshole,
+                Role::Secretary,
+                self.config.secretary_swap,
+            ));
+        }
+        let mut sends = Vec::new();
+        for (asshole_role, president_role, num_cards) in swaps {
+            if let (Some(asshole), Some(president)) =
+                (self.get_role(asshole_role), self.get_role(president_role))
+            {
+                let (asshole_id, president_id) = (asshole.state.id, president.state.id);
+                sends.extend(std::iter::repeat_n(
+                    (asshole_id, president_id, true),
+                    num_cards,
+                ));
+                sends.extend(std::iter::repeat_n(
+                    (president_id, asshole_id, false),
+                    num_cards,
+                ));
+            }
+        }
+        sends
+    }
 
-    pub fn start_new_game(&mut self) {
-        // TODO: should enable option to shuffle seating order between games. something like:
-        // players.shuffle(&mut thread_rng());
-        // let table = VecDeque::from(players);
+    /// Applies recorded events to a freshly dealt game exactly as live play would: leading
+    /// `SendCard` events are the pregame swaps, which must match the role pairs and counts in the
+    /// config with the lower role sending their highest cards, then the starting player leads and
+    /// each event must be by the player whose turn it is and a permitted action. Stops at the
+    /// first divergence. With history already present, the events continue that game instead.
+    pub fn replay_events(&mut self, events: &[Event]) -> Result<(), ReplayError> {
+        let mut started = !self.history.is_empty();
+        let expected_sends = if started {
+            vec![]
+        } else {
+            self.expected_sends()
+        };
+        let kept_starting_card = (!started && self.config.asshole_keeps_starting_card)
+            .then(|| self.starting_player_and_card().1);
+        for (idx, event) in events.iter().enumerate() {
+            let turn = idx + 1;
+            // sends only come before the first play, so `idx` counts the sends so far
+            match (started, event.action, expected_sends.get(idx)) {
+                (false, Action::SendCard { to, card }, Some(&(from, expected_to, sends_top))) => {
+                    if event.player_id != from {
+                        return Err(ReplayError::OutOfTurn {
+                            turn,
+                            event: *event,
+                            expected_player_id: from,
+                        });
+                    }
+                    let sender = &self
+                        .get_player(from)
+                        .expect("Expected sends are by seated players")
+                        .state;
+                    let valid_card = if sends_top {
+                        sender.top_k_cards(1) == [card]
+                    } else {
+                        sender.current_hand.contains(&card) && Some(card) != kept_starting_card
+                    };
+                    if to != expected_to || !valid_card {
+                        return Err(ReplayError::NotPermitted {
+                            turn,
+                            event: *event,
+                        });
+                    }
+                    let send_player = self
+                        .get_player_mut(from)
+                        .expect("Checked that the sender exists");
+                    send_player.state.current_hand.remove_card(&card);
+                    log::info!("{} did: {}", send_player.state.name, event.action);
+                    self.get_player_mut(to)
+                        .expect("Expected sends are to seated players")
+                        .state
+                        .current_hand
+                        .push(card);
+                    continue;
+                }
+                // a send nobody owes, or a play while sends are still owed
+                (false, Action::SendCard { .. }, None) | (false, _, Some(_)) => {
+                    return Err(ReplayError::NotPermitted {
+                        turn,
+                        event: *event,
+                    });
+                }
+                _ => {}
+            }
+            if !started {
+                self.set_starting_player();
+                started = true;
+            }
+
+            if !self.still_playing() {
+                return Err(ReplayError::GameOver {
+                    turn,
+                    event: *event,
+                });
+            }
+            let expected_player_id = self.current_player().state.id;
+            if event.player_id != expected_player_id {
+                return Err(ReplayError::OutOfTurn {
+                    turn,
+                    event: *event,
+                    expected_player_id,
+                });
+            }
+            if !self.permitted_actions().contains(&event.action) {
+                return Err(ReplayError::NotPermitted {
+                    turn,
+                    event: *event,
+                });
+            }
+            self.perform_ingame_action(&event.action);
+        }
+        if !started {
+            self.set_starting_player();
+        }
+        Ok(())
+    }
+
+    /// Human-readable differences from `other` in the top card, turn order, hands and history
+    /// length, e.g. to see where a replay diverged. Empty if none of those differ.
+    pub fn diff(&self, other: &GameState) -> Vec<String> {
+        let show_top =
+            |top_card: Option<CardPlay>| top_card.map_or("None".to_string(), |cp| cp.to_string());
+        let turn_order = |game_state: &GameState| {
+            game_state
+                .table
+                .iter()
+                .map(|player| player.state.name.clone())
+                .join(", ")
+        };
+        let mut diffs = Vec::new();
+        if self.top_card != other.top_card {
+            diffs.push(format!(
+                "top card is {} vs {}",
+                show_top(self.top_card),
+                show_top(other.top_card)
+            ));
+        }
+        if self.reversed != other.reversed {
+            diffs.push(format!(
+                "reversed is {} vs {}",
+                self.reversed, other.reversed
+            ));
+        }
+        let ids =
+            |game_state: &GameState| game_state.table.iter().map(|p| p.state.id).collect_vec();
+        if ids(self) != ids(other) {
+            diffs.push(format!(
+                "turn order is {} vs {}",
+                turn_order(self),
+                turn_order(other)
+            ));
+        }
+        for player in self.table.iter() {
+            let name = &player.state.name;
+            let Some(other_hand) = other.hand_of(player.state.id) else {
+                diffs.push(format!("{name} is missing from the other game"));
+                continue;
+            };
+            let hand = &player.state.current_hand;
+            let only_here = hand
+                .iter()
+                .filter(|card| !other_hand.contains(card))
+                .sorted()
+                .join(", ");
+            let only_there = other_hand
+                .iter()
+                .filter(|card| !hand.contains(card))
+                .sorted()
+                .join(", ");
+            if !only_here.is_empty() {
+                diffs.push(format!("{name} has {only_here}, which the other doesn't"));
+            }
+            if !only_there.is_empty() {
+                diffs.push(format!(
+                    "{name} is missing {only_there}, which the other has"
+                ));
+            }
+        }
+        for player in other.table.iter() {
+            if self.get_player(player.state.id).is_none() {
+                diffs.push(format!("{} is only in the other game", player.state.name));
+            }
+        }
+        if self.history.len() != other.history.len() {
+            diffs.push(format!(
+                "{} events played vs {}",
+                self.history.len(),
+                other.history.len()
+            ));
+        }
+        diffs
+    }
 
-        // scan history to assign new roles for next game
-        let mut worst_to_first = Vec::with_capacity(self.table.len());
+    /// Numbered, human-readable account of this game's plays, with a marker wherever the pile
+    /// was cleared
+    pub fn transcript(&self) -> String {
+        let player_name = |id: Uuid| {
+            self.get_player(id)
+                .map_or_else(|| id.to_string(), |p| p.state.name.clone())
+        };
+        let mut lines = Vec::with_capacity(self.history.len() + self.pile_clears.len());
+        for (idx, event) in self.history.iter().enumerate() {
+            let name = player_name(event.player_id);
+            let line = match event.action {
+                Action::PlayCards { card_play } => {
+                    format!(
+                        "{}. {name} played {} (pile now {card_play})",
+                        idx + 1,
+                        card_play.to_vec().iter().join(",")
+                    )
+                }
+                Action::PlayThenComplete { first, complete } => format!(
+                    "{}. {name} played {} then {}",
+                    idx + 1,
+                    first.to_vec().iter().join(","),
+                    complete.to_vec().iter().join(",")
+                ),
+                Action::Pass => format!("{}. {name} passed", idx + 1),
+                Action::SendCard { to, card } => {
+                    format!("{}. {name} sent {card} to {}", idx + 1, player_name(to))
+                }
+            };
+            lines.push(line);
+            if self.pile_clears.contains(&(idx + 1)) {
+                lines.push("-- pile cleared --".to_string());
+            }
+        }
+        lines.join("\n")
+    }
 
-        // asshole may still have cards left
-        for player in &self.table {
-            if !player.state.current_hand.is_empty() {
-                worst_to_first.push(player.state.id);
+    /// `history` split into rounds, each ending where the pile was cleared; the round in
+    /// progress, if any, comes last
+    pub fn rounds(&self) -> Vec<Vec<Event>> {
+        let mut rounds = Vec::with_capacity(self.pile_clears.len() + 1);
+        let mut start = 0;
+        for &end in self.pile_clears.iter().chain([&self.history.len()]) {
+            if end > start {
+                rounds.push(self.history[start..end].to_vec());
             }
+            start = end;
         }
+        rounds
+    }
+
+    /// The last `n` card plays with the name of who made them, newest last
+    pub fn recent_plays(&self, n: usize) -> Vec<(String, CardPlay)> {
+        let mut plays = self
+            .history
+            .iter()
+            .rev()
+            .flat_map(|event| {
+                let name = self
+                    .get_player(event.player_id)
+                    .map_or_else(|| event.player_id.to_string(), |p| p.state.name.clone());
+                event
+                    .action
+                    .card_plays()
+                    .into_iter()
+                    .rev()
+                    .map(move |card_play| (name.clone(), card_play))
+            })
+            .take(n)
+            .collect_vec();
+        plays.reverse();
+        plays
+    }
+
+    pub fn active_players(&self) -> impl Iterator<Item = &Player> {
+        self.table
+            .iter()
+            .filter(|player| !player.state.is_finished())
+    }
+
+    /// Whether player `id` could still go out first: nobody has gone out yet and they still
+    /// hold cards
+    pub fn can_finish_first(&self, id: Uuid) -> bool {
+        self.hand_size_of(id).is_some_and(|hand_size| hand_size > 0)
+            && self.table.iter().all(|player| !player.state.is_finished())
+    }
+
+    pub fn current_phase(&self) -> Phase {
+        if !self.still_playing() {
+            Phase::Finished
+        } else if !self.pregame_done && self.history.is_empty() {
+            Phase::Pregame
+        } else {
+            Phase::Ingame
+        }
+    }
 
+    /// Players who still hold cards
+    pub fn active_player_count(&self) -> usize {
+        self.active_players().count()
+    }
+
+    pub fn still_playing(&self) -> bool {
+        self.active_player_count() >= 2
+    }
+
+    /// Finishing order of the current game as `(player_id, place, role)`, where place 1 went out
+    /// first and `role` is what that place earns for the next game. Call this after the game
+    /// ends, before `start_new_game` clears the history. Players still holding cards are placed
+    /// per `config.last_place_rule`.
+    pub fn standings(&self) -> Vec<(Uuid, usize, Role)> {
+        // scan history backwards, starting with the players that still have cards left, last to
+        // act first
+        let mut worst_to_first: Vec<Uuid> = self
+            .table
+            .iter()
+            .rev()
+            .filter(|player| !player.state.is_finished())
+            .map(|player| player.state.id)
+            .collect();
         for &event in self.history.iter().rev() {
-            if matches!(event.action, Action::PlayCards { .. })
-                && !worst_to_first.contains(&event.player_id)
-            {
+            if event.action.plays_cards() && !worst_to_first.contains(&event.player_id) {
                 worst_to_first.push(event.player_id);
             }
         }
 
-        let results_str = worst_to_first
-            .iter()
+        let num_players = worst_to_first.len();
+        let shared_place = num_players + 1 - self.active_player_count();
+        worst_to_first
+            .into_iter()
             .rev()
             .enumerate()
             .map(|(idx, p_id)| {
+                let place = idx + 1;
+                let role = role_for_place(place, num_players);
+                match self.config.last_place_rule {
+                    LastPlaceRule::Shared if place > shared_place => (p_id, shared_place, role),
+                    LastPlaceRule::SharedAssholes if place >= shared_place => {
+                        (p_id, shared_place, Role::Asshole)
+                    }
+                    _ => (p_id, place, role),
+                }
+            })
+            .collect()
+    }
+
+    /// Hands out roles from this game's standings and deals the next game from the session's
+    /// rng, so every game of a seeded session is reproducible from its seed
+    pub fn start_new_game(&mut self) {
+        // TODO: should enable option to shuffle seating order between games. something like:
+        // players.shuffle(&mut thread_rng());
+        // let table = VecDeque::from(players);
+
+        let standings = self.standings();
+        let results_str = standings
+            .iter()
+            .map(|(p_id, place, _)| {
                 let player = self
                     .get_player(*p_id)
                     .expect("ID that played in last game should still exist");
-                format!("{}. {}", idx + 1, player.state.name)
+                format!("{}. {}", place, player.state.name)
             })
             .join("\n");
         log::info!("Game over! Results:\n{results_str}");
 
-        // NOTE: assumes all roles are being used
-
         // clear roles before assigning new roles
         for player in self.table.iter_mut() {
             player.state.role = None;
         }
 
-        if let Some(&asshole_id) = worst_to_first.get(0) {
-            let player = self
-                .get_player_mut(asshole_id)
-                .expect("ID that played in last game should still exist");
-            player.state.role = Some(Role::Asshole);
-        }
-        if let Some(&vice_asshole_id) = worst_to_first.get(1) {
-            let player = self
-                .get_player_mut(vice_asshole_id)
-                .expect("ID that played in last game should still exist");
-            player.state.role = Some(Role::ViceAsshole);
-        }
-        if let Some(&vp_id) = worst_to_first.get(worst_to_first.len() - 2) {
-            let player = self
-                .get_player_mut(vp_id)
-                .expect("ID that played in last game should still exist");
-            player.state.role = Some(Role::VicePresident);
-        }
-        if let Some(&prez_id) = worst_to_first.last() {
+        for (p_id, _, role) in standings {
             let player = self
-                .get_player_mut(prez_id)
+                .get_player_mut(p_id)
                 .expect("ID that played in last game should still exist");
-            player.state.role = Some(Role::President);
+            player.state.role = Some(role);
         }
 
         self.top_card = None;
+        self.reversed = false;
         self.history.clear();
+        self.pile_clears.clear();
+        self.passed_this_round.clear();
+        self.turns = 0;
+        self.custom_deal = false;
+        self.pregame_done = false;
 
-        let mut deck = Deck::new();
-        deck.reset_shuffle();
-        let hand_size = deck.count() / self.table.len();
-        for player in self.table.iter_mut() {
-            player.state.current_hand = deck.deal(hand_size).into_iter().map_into().collect();
+        let (hands, deck_remaining) = deal(&self.config, self.table.len(), &mut self.rng);
+        self.deck_remaining = deck_remaining;
+        for (player, cards) in self.table.iter_mut().zip(hands) {
+            player.state.current_hand = cards;
         }
 
         log::info!("New game!");
     }
 }
 
+// equal hands from a shuffled deck, and the remainder left undealt. If the remainder would hold
+// every starting card, one of them trades places with a random dealt card so someone can lead.
+fn deal(config: &GameConfig, num_players: usize, rng: &mut StdRng) -> (Vec<Vec<Card>>, Vec<Card>) {
+    let mut deck = Card::all_cards();
+    deck.shuffle(rng);
+    let hand_size = deck.len() / num_players;
+    let num_dealt = hand_size * num_players;
+    if let StartingRule::SpecificCards(starting_cards) = &config.starting_rule {
+        if starting_cards
+            .iter()
+            .all(|card| deck[num_dealt..].contains(card))
+        {
+            let undealt_idx = deck[num_dealt..]
+                .iter()
+                .position(|card| starting_cards.contains(card))
+                .expect("Starting cards are undealt")
+                + num_dealt;
+            deck.swap(undealt_idx, rng.gen_range(0..num_dealt));
+        }
+    }
+    let deck_remaining = deck.split_off(num_dealt);
+    let hands = match config.deal_mode {
+        DealMode::Random => deck.chunks(hand_size).map(<[Card]>::to_vec).collect(),
+        DealMode::Balanced => {
+            // each rank, highest first, goes out in randomly sized sets to whichever hand with
+            // room is worth least so far, so values even out while pairs and bigger sets still
+            // turn up. Nobody gets more than their share of the top rank.
+            let mut hands: Vec<Vec<Card>> = vec![Vec::with_capacity(hand_size); num_players];
+            let ranks = deck
+                .into_iter()
+                .into_group_map_by(Card::value)
+                .into_iter()
+                .sorted_by_key(|&(value, _)| std::cmp::Reverse(value))
+                .collect_vec();
+            for (idx, (_, mut cards)) in ranks.into_iter().enumerate() {
+                let max_set = match idx {
+                    0 => cards.len().div_ceil(num_players),
+                    _ => cards.len(),
+                };
+                while !cards.is_empty() {
+                    let held = |seat: &usize| {
+                        hands[*seat]
+                            .iter()
+                            .filter(|card| card.rank() == cards[0].rank())
+                            .count()
+                    };
+                    let can_take =
+                        |seat: &usize| hands[*seat].len() < hand_size && held(seat) < max_set;
+                    let hand_value =
+                        |seat: &usize| hands[*seat].iter().map(Card::value).sum::<usize>();
+                    // a hand takes one set per rank, unless only hands holding it have room
+                    let seat = (0..num_players)
+                        .filter(|seat| can_take(seat) && held(seat) == 0)
+                        .min_by_key(hand_value)
+                        .or_else(|| (0..num_players).filter(can_take).min_by_key(hand_value))
+                        .expect("Dealing no more cards than the hands hold");
+                    let room = (hand_size - hands[seat].len()).min(max_set - held(&seat));
+                    let set_size = rng.gen_range(1..=cards.len().min(room));
+                    hands[seat].extend(cards.drain(..set_size));
+                }
+            }
+            // the least valuable hands fill up first, so don't let that favor the first seats
+            hands.shuffle(rng);
+            for hand in hands.iter_mut() {
+                hand.shuffle(rng);
+            }
+            hands
+        }
+    };
+    (hands, deck_remaining)
+}
+
+// pairs of disjoint same-rank sets of `size` cards from `hand`, each pair listed once
+fn double_downs(hand: &Vec<Card>, size: usize) -> Vec<Action> {
+    hand.grouped_by_rank()
+        .into_iter()
+        .flat_map(|(_, cards)| {
+            cards
+                .iter()
+                .combinations(size)
+                .map(|set| CardPlay::from_cards(&set.into_iter().collect_vec()))
+                .tuple_combinations()
+                .filter(|(first, complete)| {
+                    let complete_cards = complete.to_vec();
+                    first
+                        .to_vec()
+                        .iter()
+                        .all(|card| !complete_cards.contains(card))
+                })
+                .map(|(first, complete)| Action::PlayThenComplete { first, complete })
+                .collect_vec()
+        })
+        .collect()
+}
+
+fn binomial(n: usize, k: usize) -> usize {
+    if k > n {
+        return 0;
+    }
+    (0..k).fold(1, |acc, i| acc * (n - i) / (i + 1))
+}
+
+// with fewer than 4 players the higher roles win out; every middle place is a Secretary
+fn role_for_place(place: usize, num_players: usize) -> Role {
+    if place == 1 {
+        Role::President
+    } else if place == 2 {
+        Role::VicePresident
+    } else if place == num_players {
+        Role::Asshole
+    } else if place + 1 == num_players {
+        Role::ViceAsshole
+    } else {
+        Role::Secretary
+    }
+}
+
 impl Display for GameState {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let top_card_str = self
@@ -465,3 +1749,1756 @@ impl Display for GameState {
         write!(f, "\nTop Card: {}\nTable:\n{}", top_card_str, players_str)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, rc::Rc};
+
+    use deckofcards::Card as DOCCard;
+
+    use super::*;
+
+    // always takes the first action it's offered
+    #[derive(Debug)]
+    struct FirstAction;
+
+    impl Strategy for FirstAction {
+        fn select_action(
+            &mut self,
+            _private_info: &PlayerState,
+            _public_info: &PublicInfo,
+            available_actions: &[Action],
+        ) -> Action {
+            available_actions[0]
+        }
+    }
+
+    // takes the first action, noting who it was asked as, who public info says is acting, and
+    // how many cards it was told it holds
+    #[derive(Debug, Default)]
+    struct Recorder(Rc<RefCell<Vec<(Uuid, Uuid, usize)>>>);
+
+    impl Strategy for Recorder {
+        fn select_action(
+            &mut self,
+            private_info: &PlayerState,
+            public_info: &PublicInfo,
+            available_actions: &[Action],
+        ) -> Action {
+            self.0.borrow_mut().push((
+                private_info.id,
+                public_info.current_player_id,
+                private_info.current_hand.len(),
+            ));
+            available_actions[0]
+        }
+    }
+
+    // picks uniformly among the actions it's offered
+    #[derive(Debug)]
+    struct SeededRandom(StdRng);
+
+    impl Strategy for SeededRandom {
+        fn select_action(
+            &mut self,
+            _private_info: &PlayerState,
+            _public_info: &PublicInfo,
+            available_actions: &[Action],
+        ) -> Action {
+            *available_actions
+                .choose(&mut self.0)
+                .expect("Always offered an action")
+        }
+    }
+
+    // gives back its lowest card that isn't part of a set, if it can
+    #[derive(Debug)]
+    struct SetKeeper;
+
+    impl Strategy for SetKeeper {
+        fn select_action(
+            &mut self,
+            private_info: &PlayerState,
+            _public_info: &PublicInfo,
+            available_actions: &[Action],
+        ) -> Action {
+            let in_set = |card: &Card| private_info.current_hand.by_rank(card.rank()).len() > 1;
+            *available_actions
+                .iter()
+                .min_by_key(|action| match action {
+                    Action::SendCard { card, .. } => (in_set(card), card.value()),
+                    _ => (true, usize::MAX),
+                })
+                .expect("Always offered an action")
+        }
+    }
+
+    thread_local! {
+        static CAPTURED_LOGS: RefCell<Vec<(log::Level, String)>> = const { RefCell::new(Vec::new()) };
+    }
+
+    // keeps each test thread's log records apart, since tests run side by side
+    struct CapturingLogger;
+
+    impl log::Log for CapturingLogger {
+        fn enabled(&self, _metadata: &log::Metadata) -> bool {
+            true
+        }
+
+        fn log(&self, record: &log::Record) {
+            let message = record.args().to_string();
+            CAPTURED_LOGS.with(|logs| logs.borrow_mut().push((record.level(), message)));
+        }
+
+        fn flush(&self) {}
+    }
+
+    // records logged at info level or above while running `f`
+    fn capture_logs(f: impl FnOnce()) -> Vec<(log::Level, String)> {
+        static LOGGER: CapturingLogger = CapturingLogger;
+        // another test may have installed it already
+        let _ = log::set_logger(&LOGGER);
+        log::set_max_level(log::LevelFilter::Info);
+        CAPTURED_LOGS.with(|logs| logs.borrow_mut().clear());
+        f();
+        CAPTURED_LOGS.with(RefCell::take)
+    }
+
+    // space-separated short cards, e.g. "5C 5S"
+    fn cards(cards_str: &str) -> Vec<Card> {
+        cards_str
+            .split_whitespace()
+            .map(|card_str| DOCCard::from_str(card_str).expect("Valid card").into())
+            .collect()
+    }
+
+    fn play(cards_str: &str) -> CardPlay {
+        CardPlay::from_cards(&cards(cards_str).iter().collect_vec())
+    }
+
+    fn players(num_players: usize) -> Vec<(String, Box<dyn Strategy>)> {
+        (0..num_players)
+            .map(|idx| {
+                (
+                    format!("p{idx}"),
+                    Box::new(FirstAction) as Box<dyn Strategy>,
+                )
+            })
+            .collect()
+    }
+
+    // full deck with `first_hand` dealt to p0, who sits first
+    fn deal_with_first_hand(num_players: usize, first_hand: &str) -> GameState {
+        let first_hand = cards(first_hand);
+        let deck = first_hand
+            .iter()
+            .copied()
+            .chain(
+                Card::all_cards()
+                    .into_iter()
+                    .filter(|card| !first_hand.contains(card)),
+            )
+            .collect();
+        GameState::new_with_deck(players(num_players), deck).expect("Valid deck")
+    }
+
+    // players p0, p1, ... seated in order with exactly these hands, p0 to act
+    fn seat(hands: &[&str]) -> GameState {
+        let players = hands
+            .iter()
+            .enumerate()
+            .map(|(idx, hand)| {
+                (
+                    Uuid::new_v4(),
+                    format!("p{idx}"),
+                    cards(hand),
+                    Box::new(FirstAction) as Box<dyn Strategy>,
+                )
+            })
+            .collect();
+        GameState::new_with_hands(players, None).expect("Valid hands")
+    }
+
+    fn current_name(game_state: &GameState) -> &str {
+        &game_state.current_player().state.name
+    }
+
+    fn play_cards(cards_str: &str) -> Action {
+        Action::PlayCards {
+            card_play: play(cards_str),
+        }
+    }
+
+    fn id_of(game_state: &GameState, name: &str) -> Uuid {
+        game_state
+            .table
+            .iter()
+            .find(|player| player.state.name == name)
+            .expect("Seated player")
+            .state
+            .id
+    }
+
+    // each player plays these in turn until the game ends
+    fn play_out(game_state: &mut GameState, plays: &[&str]) {
+        for cards_str in plays {
+            let action = if *cards_str == "pass" {
+                Action::Pass
+            } else {
+                play_cards(cards_str)
+            };
+            game_state.perform_ingame_action(&action);
+        }
+    }
+
+    fn named_standings(game_state: &GameState) -> Vec<(String, usize, Role)> {
+        game_state
+            .standings()
+            .into_iter()
+            .map(|(id, place, role)| {
+                let name = game_state.get_player(id).unwrap().state.name.clone();
+                (name, place, role)
+            })
+            .collect()
+    }
+
+    // names and hands in seating order
+    fn seating(game_state: &GameState) -> Vec<(String, Vec<Card>)> {
+        game_state
+            .table
+            .iter()
+            .map(|player| (player.state.name.clone(), player.state.current_hand.clone()))
+            .collect()
+    }
+
+    // plays whole seeded sessions with first-action players, which must never panic
+    fn play_session(game_state: &mut GameState, num_games: usize) {
+        for _ in 0..num_games {
+            game_state.run_pregame();
+            while game_state.still_playing() {
+                let available_actions = game_state.permitted_actions();
+                let public_info = game_state.public_info();
+                let player = game_state.current_player_mut();
+                let action =
+                    player
+                        .strategy
+                        .select_action(&player.state, &public_info, &available_actions);
+                game_state.perform_ingame_action(&action);
+            }
+            game_state.start_new_game();
+        }
+    }
+
+    #[test]
+    fn player_count_limits() {
+        let config = GameConfig::default;
+        for num_players in [0, 1] {
+            assert_eq!(
+                GameState::new_with_config(players(num_players), config()).unwrap_err(),
+                GameError::TooFewPlayers(num_players)
+            );
+        }
+        assert!(GameState::new_with_config(players(2), config()).is_ok());
+        assert_eq!(
+            GameState::new_with_config(players(53), config()).unwrap_err(),
+            GameError::TooManyPlayers(53)
+        );
+        for starting_rule in [StartingRule::default(), StartingRule::LowestCard] {
+            let config = GameConfig {
+                starting_rule,
+                ..config()
+            };
+            assert_eq!(
+                GameState::new_with_config(players(27), config).unwrap_err(),
+                GameError::SwapLargerThanHand {
+                    num_cards: 2,
+                    hand_size: 1
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn tables_with_many_undealt_cards_still_deal_a_starting_card() {
+        // 9 and 11 players leave 7 cards undealt, more than the 5 default starting cards
+        for num_players in [9, 11] {
+            for seed in 0..5 {
+                let mut game_state =
+                    GameState::new_with_seed(players(num_players), GameConfig::default(), seed)
+                        .unwrap();
+                play_session(&mut game_state, 2);
+            }
+        }
+        // a lone starting card would otherwise be undealt in about 1 of every 7 deals
+        let ace_leads = GameConfig {
+            starting_rule: StartingRule::SpecificCards(cards("AS")),
+            ..GameConfig::default()
+        };
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..200 {
+            let (_, deck_remaining) = deal(&ace_leads, 9, &mut rng);
+            assert_eq!(deck_remaining.len(), 7);
+            assert!(!deck_remaining.contains(&cards("AS")[0]));
+        }
+        // every starting card in the last 7 cards of a scripted deck
+        let mut deck = Card::all_cards();
+        let starting_cards = cards("3C 3S 3H 3D 4C");
+        deck.retain(|card| !starting_cards.contains(card));
+        deck.extend(starting_cards);
+        assert_eq!(
+            GameState::new_with_deck(players(9), deck).unwrap_err(),
+            GameError::StartingCardsUndealt
+        );
+    }
+
+    #[test]
+    fn swaps_must_fit_in_a_hand() {
+        let big_swap = GameConfig {
+            president_swap: 13,
+            asshole_keeps_starting_card: false,
+            ..GameConfig::default()
+        };
+        assert!(GameState::new_with_config(players(4), big_swap.clone()).is_ok());
+        let keeps_starting_card = GameConfig {
+            asshole_keeps_starting_card: true,
+            ..big_swap
+        };
+        assert_eq!(
+            GameState::new_with_config(players(4), keeps_starting_card).unwrap_err(),
+            GameError::SwapLargerThanHand {
+                num_cards: 13,
+                hand_size: 13
+            }
+        );
+    }
+
+    #[test]
+    fn every_accepted_table_size_plays_out() {
+        for num_players in 2..=52 {
+            for starting_rule in [StartingRule::default(), StartingRule::LowestCard] {
+                let config = GameConfig {
+                    starting_rule,
+                    secretary_swap: 1,
+                    ..GameConfig::default()
+                };
+                for seed in 0..5 {
+                    if let Ok(mut game_state) =
+                        GameState::new_with_seed(players(num_players), config.clone(), seed)
+                    {
+                        play_session(&mut game_state, 2);
+                    }
+                }
+            }
+        }
+    }
+
+    // first-action players with roles from one finished game
+    fn after_one_game_with(num_players: usize, config: GameConfig) -> GameState {
+        let mut game_state =
+            GameState::new_with_seed(players(num_players), config, 0).expect("Valid setup");
+        play_session(&mut game_state, 1);
+        game_state
+    }
+
+    fn after_one_game() -> GameState {
+        after_one_game_with(4, GameConfig::default())
+    }
+
+    // (sender's role, receiver's role) of each pregame send
+    fn send_roles(game_state: &mut GameState) -> Vec<(Role, Role)> {
+        let role_of = |game_state: &GameState, id| {
+            game_state
+                .get_player(id)
+                .and_then(|player| player.state.role)
+                .expect("Only players with roles swap")
+        };
+        game_state
+            .run_pregame()
+            .into_iter()
+            .map(|event| match event.action {
+                Action::SendCard { to, .. } => (
+                    role_of(game_state, event.player_id),
+                    role_of(game_state, to),
+                ),
+                action => panic!("{action} in pregame"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn president_never_sends_back_the_kept_starting_card() {
+        // how many pregames had the President or VP send the starting card back
+        let starting_card_sends = |asshole_keeps_starting_card| {
+            let config = GameConfig {
+                asshole_keeps_starting_card,
+                ..GameConfig::default()
+            };
+            (0..20)
+                .filter(|&seed| {
+                    let mut game_state =
+                        GameState::new_with_seed(players(4), config.clone(), seed).unwrap();
+                    play_session(&mut game_state, 1);
+                    let starting_card = game_state.starting_player_and_card().1;
+                    game_state.run_pregame().into_iter().any(|event| {
+                        let sender_role = game_state
+                            .get_player(event.player_id)
+                            .and_then(|player| player.state.role);
+                        matches!(sender_role, Some(Role::President | Role::VicePresident))
+                            && matches!(event.action, Action::SendCard { card, .. } if card == starting_card)
+                    })
+                })
+                .count()
+        };
+        assert_eq!(starting_card_sends(true), 0);
+        assert!(starting_card_sends(false) > 0);
+    }
+
+    #[test]
+    fn replayed_sends_follow_the_swap_rules() {
+        let mut live = after_one_game();
+        let sends = live.run_pregame();
+        assert_eq!(sends.len(), 6);
+        let mut replayed = after_one_game();
+        replayed.replay_events(&sends).unwrap();
+        for player in &live.table {
+            assert_eq!(
+                replayed.hand_of(player.state.id),
+                Some(player.state.current_hand.as_slice())
+            );
+        }
+
+        let game_state = after_one_game();
+        let asshole = &game_state.get_role(Role::Asshole).unwrap().state;
+        let president_id = game_state.get_role(Role::President).unwrap().state.id;
+        let replay_err = |events: &[Event]| after_one_game().replay_events(events).unwrap_err();
+
+        // the asshole has to send their best card
+        let mut low_send = sends.clone();
+        low_send[0].action = Action::SendCard {
+            to: president_id,
+            card: asshole.bottom_k_cards(1)[0],
+        };
+        assert_eq!(
+            replay_err(&low_send),
+            ReplayError::NotPermitted {
+                turn: 1,
+                event: low_send[0]
+            }
+        );
+
+        let mut wrong_receiver = sends.clone();
+        wrong_receiver[0].action = Action::SendCard {
+            to: game_state.get_role(Role::VicePresident).unwrap().state.id,
+            card: asshole.top_k_cards(1)[0],
+        };
+        assert_eq!(
+            replay_err(&wrong_receiver),
+            ReplayError::NotPermitted {
+                turn: 1,
+                event: wrong_receiver[0]
+            }
+        );
+
+        let mut wrong_sender = sends.clone();
+        wrong_sender[1].player_id = president_id;
+        assert_eq!(
+            replay_err(&wrong_sender),
+            ReplayError::OutOfTurn {
+                turn: 2,
+                event: wrong_sender[1],
+                expected_player_id: asshole.id
+            }
+        );
+
+        // a play while the last send is still owed, and a send nobody owes
+        let mut early_play = sends[..5].to_vec();
+        early_play.push(Event {
+            player_id: asshole.id,
+            action: Action::Pass,
+        });
+        assert_eq!(
+            replay_err(&early_play),
+            ReplayError::NotPermitted {
+                turn: 6,
+                event: early_play[5]
+            }
+        );
+        let mut extra_send = sends.clone();
+        extra_send.push(sends[0]);
+        assert_eq!(
+            replay_err(&extra_send),
+            ReplayError::NotPermitted {
+                turn: 7,
+                event: sends[0]
+            }
+        );
+
+        // nobody swaps before the first game
+        let mut first_game =
+            GameState::new_with_seed(players(4), GameConfig::default(), 0).unwrap();
+        assert_eq!(
+            first_game.replay_events(&sends[..1]).unwrap_err(),
+            ReplayError::NotPermitted {
+                turn: 1,
+                event: sends[0]
+            }
+        );
+    }
+
+    #[test]
+    fn legality_checks_agree_with_permitted_actions() {
+        for seed in 0..60 {
+            let num_players = 2 + (seed % 6) as usize;
+            let player_inputs = (0..num_players)
+                .map(|idx| {
+                    let strategy = SeededRandom(StdRng::seed_from_u64(seed * 10 + idx as u64));
+                    (format!("p{idx}"), Box::new(strategy) as Box<dyn Strategy>)
+                })
+                .collect();
+            let config = GameConfig {
+                twos_clear_pile: seed % 2 == 0,
+                sticky_passes: seed % 3 == 0,
+                quads_reverse: seed % 5 != 0,
+                double_down_clears: seed % 7 < 4,
+                allow_breaking_sets: seed % 4 != 0,
+                open_with_single: seed % 3 == 1,
+                ..GameConfig::default()
+            };
+            let mut game_state = GameState::new_with_seed(player_inputs, config, seed).unwrap();
+            for _ in 0..3 {
+                game_state.run_pregame();
+                while game_state.still_playing() {
+                    let available_actions = game_state.permitted_actions();
+                    assert_eq!(
+                        available_actions.len(),
+                        game_state.legal_action_count(),
+                        "seed {seed}"
+                    );
+                    // every set in the hand, legal or not, plus whatever else is permitted
+                    let hand = &game_state.current_player().state.current_hand;
+                    let candidates = [hand.singles(), hand.pairs(), hand.triples(), hand.quads()]
+                        .concat()
+                        .into_iter()
+                        .map(|card_play| Action::PlayCards { card_play })
+                        .chain([Action::Pass])
+                        .chain(available_actions.iter().copied());
+                    for action in candidates {
+                        assert_eq!(
+                            game_state.is_action_legal(&action),
+                            available_actions.contains(&action),
+                            "seed {seed}: {action}"
+                        );
+                    }
+                    let public_info = game_state.public_info();
+                    let player = game_state.current_player_mut();
+                    let action = player.strategy.select_action(
+                        &player.state,
+                        &public_info,
+                        &available_actions,
+                    );
+                    game_state.perform_ingame_action(&action);
+                }
+                game_state.start_new_game();
+            }
+        }
+    }
+
+    #[test]
+    fn twos_clear_the_pile_when_configured() {
+        let mut game_state = seat(&["2C 7C", "9C", "KC"]);
+        game_state.perform_ingame_action(&play_cards("2C"));
+        assert_eq!(game_state.top_card, Some(play("2C")));
+        assert_eq!(current_name(&game_state), "p1");
+
+        let mut game_state = seat(&["2C 7C", "9C", "KC"]);
+        game_state.config.twos_clear_pile = true;
+        game_state.perform_ingame_action(&play_cards("2C"));
+        assert_eq!(game_state.top_card, None);
+        assert_eq!(current_name(&game_state), "p0");
+
+        // going out on a 2 still passes the lead on
+        let mut game_state = seat(&["2C", "9C", "KC"]);
+        game_state.config.twos_clear_pile = true;
+        game_state.perform_ingame_action(&play_cards("2C"));
+        assert_eq!(game_state.top_card, None);
+        assert_eq!(current_name(&game_state), "p1");
+    }
+
+    #[test]
+    fn standings_follow_the_order_players_went_out() {
+        let mut game_state = seat(&["3C", "4C", "5C", "6C 7C"]);
+        play_out(&mut game_state, &["3C", "4C", "5C"]);
+        assert!(!game_state.still_playing());
+        let expected = [
+            ("p0", 1, Role::President),
+            ("p1", 2, Role::VicePresident),
+            ("p2", 3, Role::ViceAsshole),
+            ("p3", 4, Role::Asshole),
+        ];
+        assert_eq!(
+            named_standings(&game_state),
+            expected.map(|(name, place, role)| (name.to_string(), place, role))
+        );
+
+        game_state.start_new_game();
+        for (name, _, role) in expected {
+            let id = id_of(&game_state, name);
+            assert_eq!(game_state.get_player(id).unwrap().state.role, Some(role));
+        }
+    }
+
+    #[test]
+    fn seeded_rng_gives_the_same_deal_and_pregame() {
+        let new_game = |seed| {
+            let rng = StdRng::seed_from_u64(seed);
+            GameState::new_with_rng(players(4), GameConfig::default(), rng).unwrap()
+        };
+        let (mut first, mut second) = (new_game(3), new_game(3));
+        assert_eq!(seating(&first), seating(&second));
+        assert_ne!(seating(&first), seating(&new_game(4)));
+
+        play_session(&mut first, 1);
+        play_session(&mut second, 1);
+        assert_eq!(seating(&first), seating(&second));
+        let sends = |game_state: &mut GameState| {
+            game_state
+                .run_pregame()
+                .into_iter()
+                .map(|event| event.action)
+                .collect_vec()
+        };
+        let first_sends = sends(&mut first);
+        assert_eq!(first_sends.len(), 6);
+        assert_eq!(first_sends, sends(&mut second));
+        assert_eq!(seating(&first), seating(&second));
+    }
+
+    #[test]
+    fn whole_sessions_replay_from_one_seed() {
+        // roles each game handed out, and the deal that followed
+        let session = |seed| {
+            let mut game_state =
+                GameState::new_with_seed(players(4), GameConfig::default(), seed).unwrap();
+            (0..3)
+                .map(|_| {
+                    play_session(&mut game_state, 1);
+                    let roles = game_state
+                        .table
+                        .iter()
+                        .map(|player| (player.state.name.clone(), player.state.role))
+                        .collect_vec();
+                    (roles, seating(&game_state))
+                })
+                .collect_vec()
+        };
+        assert_eq!(session(5), session(5));
+        assert_ne!(session(5), session(6));
+    }
+
+    #[test]
+    fn pregame_senders_see_the_hand_they_keep() {
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let player_inputs = (0..4)
+            .map(|idx| {
+                let strategy = Recorder(Rc::clone(&calls));
+                (format!("p{idx}"), Box::new(strategy) as Box<dyn Strategy>)
+            })
+            .collect();
+        let mut game_state =
+            GameState::new_with_seed(player_inputs, GameConfig::default(), 0).unwrap();
+        play_session(&mut game_state, 1);
+        calls.borrow_mut().clear();
+
+        game_state.run_pregame();
+        let president_id = game_state.get_role(Role::President).unwrap().state.id;
+        let vp_id = game_state.get_role(Role::VicePresident).unwrap().state.id;
+        // the President picks 2 cards to send back, each time from what they'd keep, then the
+        // VicePresident picks 1
+        assert_eq!(
+            *calls.borrow(),
+            [
+                (president_id, president_id, 13),
+                (president_id, president_id, 12),
+                (vp_id, vp_id, 13),
+            ]
+        );
+    }
+
+    #[test]
+    fn set_aware_senders_can_keep_a_pair_together() {
+        let mut game_state = after_one_game();
+        let president_id = game_state.get_role(Role::President).unwrap().state.id;
+        // the President's lowest cards are 4♣, then a pair of 5s, then 6♣
+        let low_cards = cards("4C 5C 5S 6C");
+        let mut rest = Card::all_cards()
+            .into_iter()
+            .filter(|card| !low_cards.contains(card))
+            .sorted_by_key(|card| std::cmp::Reverse(card.value()))
+            .collect_vec();
+        for player in game_state.table.iter_mut() {
+            player.state.current_hand = if player.state.id == president_id {
+                player.strategy = Box::new(SetKeeper);
+                low_cards.iter().copied().chain(rest.drain(..9)).collect()
+            } else {
+                rest.drain(..13).collect()
+            };
+        }
+
+        let sent = game_state
+            .run_pregame()
+            .into_iter()
+            .filter(|event| event.player_id == president_id)
+            .map(|event| event.action)
+            .collect_vec();
+        let asshole_id = game_state.get_role(Role::Asshole).unwrap().state.id;
+        let send = |card_str| Action::SendCard {
+            to: asshole_id,
+            card: cards(card_str)[0],
+        };
+        assert_eq!(sent, [send("4C"), send("6C")]);
+        let president_hand = game_state.hand_of(president_id).unwrap();
+        assert!(cards("5C 5S")
+            .iter()
+            .all(|card| president_hand.contains(card)));
+    }
+
+    #[test]
+    fn transcript_lists_each_turn_and_pile_clear() {
+        let mut game_state = seat(&["3C 9C", "4C KC", "5C 6C"]);
+        play_out(&mut game_state, &["3C", "4C", "pass", "pass", "KC"]);
+        assert_eq!(
+            game_state.transcript(),
+            "1. p0 played 3♣ (pile now (3♣))
+2. p1 played 4♣ (pile now (4♣))
+3. p2 passed
+4. p0 passed
+-- pile cleared --
+5. p1 played K♣ (pile now (K♣))"
+        );
+    }
+
+    #[test]
+    fn rounds_split_history_where_the_pile_cleared() {
+        let mut game_state = seat(&["3C 9C", "4C KC", "5C 6C"]);
+        assert!(game_state.rounds().is_empty());
+        play_out(&mut game_state, &["3C", "4C", "pass", "pass", "KC"]);
+        let actions = game_state
+            .rounds()
+            .into_iter()
+            .map(|round| round.into_iter().map(|event| event.action).collect_vec())
+            .collect_vec();
+        assert_eq!(
+            actions,
+            [
+                vec![
+                    play_cards("3C"),
+                    play_cards("4C"),
+                    Action::Pass,
+                    Action::Pass
+                ],
+                vec![play_cards("KC")],
+            ]
+        );
+    }
+
+    #[test]
+    fn recent_plays_skip_passes_and_name_the_player() {
+        let mut game_state = seat(&["3C 9C", "4C KC", "5C 6C"]);
+        play_out(&mut game_state, &["3C", "4C", "pass", "pass", "KC"]);
+        let named = |name: &str, cards_str| (name.to_string(), play(cards_str));
+        assert_eq!(
+            game_state.recent_plays(2),
+            [named("p1", "4C"), named("p1", "KC")]
+        );
+        assert_eq!(
+            game_state.recent_plays(10),
+            [named("p0", "3C"), named("p1", "4C"), named("p1", "KC")]
+        );
+    }
+
+    #[test]
+    fn leftover_cards_stay_undealt_each_game() {
+        let config = GameConfig {
+            starting_rule: StartingRule::LowestCard,
+            ..GameConfig::default()
+        };
+        let mut game_state = GameState::new_with_seed(players(5), config, 7).unwrap();
+        for _ in 0..2 {
+            assert_eq!(game_state.deck_remaining().len(), 2);
+            let mut all_cards = game_state
+                .table
+                .iter()
+                .flat_map(|player| player.state.current_hand.clone())
+                .chain(game_state.deck_remaining().iter().copied())
+                .map(|card| card.to_string())
+                .collect_vec();
+            all_cards.sort();
+            all_cards.dedup();
+            assert_eq!(all_cards.len(), 52);
+            game_state.start_new_game();
+        }
+        let four_players = GameState::new_with_seed(players(4), GameConfig::default(), 7).unwrap();
+        assert!(four_players.deck_remaining().is_empty());
+    }
+
+    #[test]
+    fn active_player_count_drops_as_players_go_out() {
+        let mut game_state = seat(&["3C", "4C", "6C 7C"]);
+        assert_eq!(game_state.active_player_count(), 3);
+        game_state.perform_ingame_action(&play_cards("3C"));
+        assert_eq!(game_state.active_player_count(), 2);
+        assert!(game_state.still_playing());
+        game_state.perform_ingame_action(&play_cards("4C"));
+        assert_eq!(game_state.active_player_count(), 1);
+        assert!(!game_state.still_playing());
+    }
+
+    #[test]
+    fn public_info_displays_each_player_from_the_one_to_act() {
+        let mut game_state = seat(&["3C 9C", "4C KC", "5C 6C"]);
+        play_out(&mut game_state, &["3C", "4C"]);
+        let public_info = game_state.public_info();
+        assert_eq!(
+            public_info.to_string(),
+            "p2 (None) has 2 cards left and has played: [  ]
+p0 (None) has 1 cards left and has played: [ (3♣) ]
+p1 (None) has 1 cards left and has played: [ (4♣) ]
+Top card is: (4♣)"
+        );
+        assert_eq!(
+            public_info.player_lines().collect_vec(),
+            [
+                "p2 (None) has 2 cards left and has played: [  ]",
+                "p0 (None) has 1 cards left and has played: [ (3♣) ]",
+                "p1 (None) has 1 cards left and has played: [ (4♣) ]",
+            ]
+        );
+    }
+
+    #[test]
+    fn minimal_beating_play_is_the_smallest_then_lowest() {
+        let mut game_state = seat(&["3C 3S 8C", "4C 5C 5S 9C 9S KC", "6C"]);
+        assert_eq!(game_state.minimal_beating_play(), Some(play_cards("3C")));
+        game_state.perform_ingame_action(&play_cards("3C"));
+        assert_eq!(game_state.minimal_beating_play(), Some(play_cards("4C")));
+        game_state.top_card = Some(play("4H 4D"));
+        assert_eq!(game_state.minimal_beating_play(), Some(play_cards("5C 5S")));
+        game_state.top_card = Some(play("AH AD"));
+        assert_eq!(game_state.minimal_beating_play(), None);
+    }
+
+    #[test]
+    fn breaking_a_lone_triple_depends_on_the_config() {
+        let facing = |allow_breaking_sets, top_card| {
+            let mut game_state = seat(&["3C", "7C 7S 7H 9C", "6C"]);
+            game_state.config.allow_breaking_sets = allow_breaking_sets;
+            game_state.perform_ingame_action(&play_cards("3C"));
+            game_state.top_card = Some(play(top_card));
+            game_state.permitted_actions()
+        };
+        assert_eq!(
+            facing(true, "4C"),
+            [
+                play_cards("7C"),
+                play_cards("7S"),
+                play_cards("7H"),
+                play_cards("9C"),
+                Action::Pass
+            ]
+        );
+        assert_eq!(facing(false, "4C"), [play_cards("9C"), Action::Pass]);
+        assert_eq!(facing(true, "4C 4S").len(), 4);
+        assert_eq!(facing(false, "4C 4S"), [Action::Pass]);
+        assert_eq!(facing(false, "4C 4S 4H"), facing(true, "4C 4S 4H"));
+        assert_eq!(
+            facing(false, "4C 4S 4H"),
+            [play_cards("7C 7S 7H"), Action::Pass]
+        );
+    }
+
+    #[test]
+    fn diff_names_the_player_whose_hand_changed() {
+        let game_state = seat(&["3C 9C", "4C KC", "5C 6C"]);
+        let mut copy = game_state.copy_with_strategies(|| Box::new(FirstAction));
+        assert!(game_state.diff(&copy).is_empty());
+        copy.table[1]
+            .state
+            .current_hand
+            .retain(|card| card.to_string() != "K♣");
+        assert_eq!(
+            game_state.diff(&copy),
+            ["p1 has K♣, which the other doesn't"]
+        );
+        assert_eq!(
+            copy.diff(&game_state),
+            ["p1 is missing K♣, which the other has"]
+        );
+    }
+
+    #[test]
+    fn turns_taken_counts_ingame_actions_only() {
+        let mut game_state = seat(&["3C 9C", "4C KC", "5C 6C"]);
+        play_out(&mut game_state, &["3C", "4C", "pass", "pass", "KC"]);
+        assert_eq!(game_state.turns_taken(), 5);
+        game_state.start_new_game();
+        assert_eq!(game_state.turns_taken(), 0);
+
+        let mut game_state = after_one_game();
+        assert_eq!(game_state.run_pregame().len(), 6);
+        assert_eq!(game_state.turns_taken(), 0);
+    }
+
+    #[test]
+    fn action_logs_name_the_strategy() {
+        let mut game_state = seat(&["3C 9C", "4C KC"]);
+        let logs = capture_logs(|| game_state.perform_ingame_action(&play_cards("3C")));
+        assert!(logs.contains(&(log::Level::Info, "p0 (unknown) did: Play 3♣".to_string())));
+    }
+
+    #[test]
+    fn open_with_single_offers_only_the_lone_starting_card() {
+        let opening = |open_with_single| {
+            let mut game_state = deal_with_first_hand(2, "3C 3S 9C");
+            game_state.config.open_with_single = open_with_single;
+            game_state.run_pregame();
+            game_state.permitted_actions()
+        };
+        assert_eq!(opening(true), [play_cards("3C")]);
+        let opening_plays = opening(false);
+        assert!(opening_plays.contains(&play_cards("3C 3S")));
+        assert!(opening_plays
+            .iter()
+            .all(|action| action.card_plays()[0].to_vec().contains(&cards("3C")[0])));
+    }
+
+    #[test]
+    fn player_views_show_only_their_own_hand() {
+        let game_state = seat(&["3C 9C", "4C KC", "5C 6C"]);
+        assert!(game_state.view_for(Uuid::new_v4()).is_none());
+        let view = game_state.view_for(id_of(&game_state, "p1")).unwrap();
+        let json = serde_json::to_string(&view).unwrap();
+        assert!(json.contains(r#""current_hand":["4C","KC"]"#));
+        for other_card in ["3C", "9C", "5C", "6C"] {
+            assert!(
+                !json.contains(other_card),
+                "{other_card} leaked into {json}"
+            );
+        }
+
+        let view: PlayerView = serde_json::from_str(&json).unwrap();
+        assert_eq!(view.player.name, "p1");
+        let hand_sizes = view
+            .public_info
+            .public_table
+            .iter()
+            .map(|player| (player.name.as_str(), player.hand_size))
+            .collect_vec();
+        assert_eq!(hand_sizes, [("p0", 2), ("p1", 2), ("p2", 2)]);
+    }
+
+    #[test]
+    fn undominated_actions_keep_the_lowest_play_of_each_size() {
+        let mut game_state = seat(&["3C", "4C 5C 5S 9C 9S KC", "6C"]);
+        game_state.perform_ingame_action(&play_cards("3C"));
+        assert_eq!(
+            game_state.undominated_actions(),
+            [play_cards("4C"), Action::Pass]
+        );
+        game_state.top_card = None;
+        assert_eq!(
+            game_state.undominated_actions(),
+            [play_cards("4C"), play_cards("5C 5S")]
+        );
+
+        // always a non-empty subset of what's permitted
+        let mut game_state =
+            GameState::new_with_seed(players(5), GameConfig::default(), 2).unwrap();
+        game_state.run_pregame();
+        while game_state.still_playing() {
+            let permitted = game_state.permitted_actions();
+            let undominated = game_state.undominated_actions();
+            assert!(!undominated.is_empty());
+            assert!(undominated.iter().all(|action| permitted.contains(action)));
+            game_state.perform_ingame_action(&permitted[0]);
+        }
+    }
+
+    #[test]
+    fn nobody_can_finish_first_once_someone_is_out() {
+        let mut game_state = seat(&["3C", "4C KC", "5C 6C"]);
+        let ids = ["p0", "p1", "p2"].map(|name| id_of(&game_state, name));
+        assert!(ids.iter().all(|&id| game_state.can_finish_first(id)));
+        assert!(!game_state.can_finish_first(Uuid::new_v4()));
+        game_state.perform_ingame_action(&play_cards("3C"));
+        assert!(ids.iter().all(|&id| !game_state.can_finish_first(id)));
+    }
+
+    #[test]
+    fn player_states_carry_the_roles_just_assigned() {
+        let mut game_state = seat(&["3C", "4C", "5C 6C"]);
+        play_out(&mut game_state, &["3C", "4C"]);
+        let standings = named_standings(&game_state)
+            .into_iter()
+            .map(|(name, _, role)| (name, Some(role)))
+            .sorted_by_key(|(name, _)| name.clone())
+            .collect_vec();
+        game_state.start_new_game();
+        let states = game_state.player_states();
+        let roles = states
+            .iter()
+            .map(|state| (state.name.clone(), state.role))
+            .sorted_by_key(|(name, _)| name.clone())
+            .collect_vec();
+        assert_eq!(roles, standings);
+        assert_eq!(game_state.into_player_states(), states);
+    }
+
+    #[test]
+    fn cached_card_plays_match_a_fresh_computation() {
+        let check_cache = |game_state: &GameState| {
+            let cached = game_state.permitted_actions();
+            game_state.card_play_cache.replace(None);
+            assert_eq!(cached, game_state.permitted_actions());
+        };
+        let mut game_state = after_one_game();
+        // fills the cache with a hand the pregame swaps are about to change
+        check_cache(&game_state);
+        game_state.run_pregame();
+        check_cache(&game_state);
+        while game_state.still_playing() {
+            let action = game_state.permitted_actions()[0];
+            game_state.perform_ingame_action(&action);
+            check_cache(&game_state);
+        }
+    }
+
+    #[test]
+    fn swap_counts_come_from_the_config() {
+        let config = GameConfig {
+            president_swap: 3,
+            vp_swap: 0,
+            ..GameConfig::default()
+        };
+        let mut game_state = after_one_game_with(4, config);
+        let (asshole, president) = (Role::Asshole, Role::President);
+        assert_eq!(
+            send_roles(&mut game_state),
+            [[(asshole, president); 3], [(president, asshole); 3]].concat()
+        );
+        assert!(game_state
+            .table
+            .iter()
+            .all(|player| player.state.current_hand.len() == 13));
+    }
+
+    #[test]
+    fn three_card_president_swaps_move_three_cards_each_way() {
+        let config = GameConfig {
+            president_swap: 3,
+            vp_swap: 0,
+            ..GameConfig::default()
+        };
+        let mut game_state = after_one_game_with(4, config);
+        let hands_before: HashMap<Uuid, Vec<Card>> = game_state
+            .table
+            .iter()
+            .map(|player| (player.state.id, player.state.current_hand.clone()))
+            .collect();
+        let id_with_role = |game_state: &GameState, role| {
+            game_state
+                .table
+                .iter()
+                .find(|player| player.state.role == Some(role))
+                .map(|player| player.state.id)
+                .expect("Every role is held after a game")
+        };
+        let president = id_with_role(&game_state, Role::President);
+        let asshole = id_with_role(&game_state, Role::Asshole);
+
+        let sends = game_state
+            .run_pregame()
+            .into_iter()
+            .map(|event| match event.action {
+                Action::SendCard { card, to } => (event.player_id, card, to),
+                other => panic!("Unexpected pregame action {other}"),
+            })
+            .collect_vec();
+        let sent_between = |from, to| {
+            sends
+                .iter()
+                .filter(|(sender, _, receiver)| *sender == from && *receiver == to)
+                .map(|(_, card, _)| *card)
+                .collect_vec()
+        };
+        assert_eq!(sent_between(asshole, president).len(), 3);
+        assert_eq!(sent_between(president, asshole).len(), 3);
+        assert_eq!(sends.len(), 6);
+
+        for player in &game_state.table {
+            let id = player.state.id;
+            let mut expected = hands_before[&id].clone();
+            for (sender, card, receiver) in &sends {
+                if *receiver == id {
+                    expected.push(*card);
+                }
+                if *sender == id {
+                    let idx = expected
+                        .iter()
+                        .position(|held| held == card)
+                        .expect("Only held cards are sent");
+                    expected.remove(idx);
+                }
+            }
+            // cards of equal value compare equal, so sort by suit too
+            let sorted = |hand: &[Card]| hand.iter().map(Card::to_string).sorted().collect_vec();
+            assert_eq!(
+                sorted(&player.state.current_hand),
+                sorted(&expected),
+                "{}'s hand",
+                player.state.name
+            );
+            assert_eq!(player.state.current_hand.len(), 13);
+        }
+        // the middle players neither send nor receive
+        let untouched = game_state
+            .table
+            .iter()
+            .filter(|player| ![president, asshole].contains(&player.state.id))
+            .all(|player| player.state.current_hand == hands_before[&player.state.id]);
+        assert!(untouched);
+    }
+
+    #[test]
+    fn players_who_went_out_are_no_longer_active() {
+        let mut game_state = seat(&["3C", "4C 5C", "6C 7C"]);
+        assert!(game_state
+            .table
+            .iter()
+            .all(|player| !player.state.is_finished()));
+        game_state.perform_ingame_action(&play_cards("3C"));
+        let p0 = game_state.get_player(id_of(&game_state, "p0")).unwrap();
+        assert!(p0.state.is_finished());
+        let active = game_state
+            .active_players()
+            .map(|player| player.state.name.as_str())
+            .collect_vec();
+        assert_eq!(active, ["p1", "p2"]);
+    }
+
+    #[test]
+    fn sticky_passes_sit_out_until_the_pile_clears() {
+        let hands = ["3C 9C JC", "4C KC", "5C 6C"];
+        let mut game_state = seat(&hands);
+        play_out(&mut game_state, &["3C", "pass", "5C", "9C"]);
+        assert_eq!(current_name(&game_state), "p1");
+
+        let mut game_state = seat(&hands);
+        game_state.config.sticky_passes = true;
+        play_out(&mut game_state, &["3C", "pass", "5C", "9C"]);
+        assert_eq!(current_name(&game_state), "p2");
+        // p2 passing ends the round, and p1 is back for the next one
+        play_out(&mut game_state, &["pass", "JC"]);
+        assert_eq!(current_name(&game_state), "p1");
+    }
+
+    #[test]
+    fn public_info_lists_sticky_passes_until_the_pile_clears() {
+        let hands = ["3C 9C JC", "4C KC", "5C 6C"];
+        let mut game_state = seat(&hands);
+        play_out(&mut game_state, &["3C", "pass"]);
+        assert!(game_state.public_info().passed_this_round.is_empty());
+
+        let mut game_state = seat(&hands);
+        game_state.config.sticky_passes = true;
+        play_out(&mut game_state, &["3C", "pass", "5C", "9C"]);
+        let p1 = id_of(&game_state, "p1");
+        assert_eq!(game_state.public_info().passed_this_round, [p1]);
+        play_out(&mut game_state, &["pass"]);
+        assert!(game_state.public_info().passed_this_round.is_empty());
+    }
+
+    #[test]
+    fn quads_reverse_rankings_when_configured() {
+        let hands = ["5C 5S 5H 5D 9C", "4C 4S 4H 4D KC", "6C 6S 6H 6D 7C"];
+        let mut game_state = seat(&hands);
+        play_out(&mut game_state, &["5C 5S 5H 5D"]);
+        assert!(!game_state.reversed);
+        assert!(!game_state.is_action_legal(&play_cards("4C 4S 4H 4D")));
+
+        let mut game_state = seat(&hands);
+        game_state.config.quads_reverse = true;
+        play_out(&mut game_state, &["5C 5S 5H 5D"]);
+        assert!(game_state.reversed);
+        assert!(game_state.is_action_legal(&play_cards("4C 4S 4H 4D")));
+        // a second quad flips the rankings back
+        play_out(&mut game_state, &["4C 4S 4H 4D"]);
+        assert!(!game_state.reversed);
+        assert!(game_state.is_action_legal(&play_cards("6C 6S 6H 6D")));
+
+        // the reversal ends when the pile clears
+        let mut game_state = seat(&hands);
+        game_state.config.quads_reverse = true;
+        play_out(&mut game_state, &["5C 5S 5H 5D", "pass", "pass"]);
+        assert_eq!(game_state.top_card, None);
+        assert!(!game_state.reversed);
+        play_out(&mut game_state, &["9C"]);
+        assert!(game_state.is_action_legal(&play_cards("KC")));
+        assert!(!game_state.is_action_legal(&play_cards("4C")));
+
+        // and when the game ends
+        let mut game_state = seat(&hands);
+        game_state.config.quads_reverse = true;
+        play_out(&mut game_state, &["5C 5S 5H 5D"]);
+        game_state.start_new_game();
+        assert!(!game_state.reversed);
+    }
+
+    #[test]
+    fn secretary_swaps_only_with_five_or_more_players() {
+        let config = GameConfig {
+            president_swap: 0,
+            vp_swap: 0,
+            secretary_swap: 1,
+            ..GameConfig::default()
+        };
+        let mut five_players = after_one_game_with(5, config.clone());
+        let roles = five_players
+            .table
+            .iter()
+            .filter_map(|player| player.state.role)
+            .sorted_by_key(|role| format!("{role:?}"))
+            .collect_vec();
+        assert_eq!(
+            roles,
+            [
+                Role::Asshole,
+                Role::President,
+                Role::Secretary,
+                Role::ViceAsshole,
+                Role::VicePresident
+            ]
+        );
+        assert_eq!(
+            send_roles(&mut five_players),
+            [
+                (Role::ViceAsshole, Role::Secretary),
+                (Role::Secretary, Role::ViceAsshole)
+            ]
+        );
+
+        let mut four_players = after_one_game_with(4, config);
+        assert!(four_players.get_role(Role::Secretary).is_none());
+        assert_eq!(send_roles(&mut four_players), []);
+    }
+
+    #[test]
+    fn deck_seed_rebuilds_the_same_deal() {
+        let game_state = GameState::new_with_seed(players(4), GameConfig::default(), 42).unwrap();
+        assert_eq!(game_state.deck_seed(), "42");
+        let seed = game_state.deck_seed().parse().unwrap();
+        let rebuilt = GameState::new_with_seed(players(4), GameConfig::default(), seed).unwrap();
+        assert_eq!(seating(&rebuilt), seating(&game_state));
+
+        let unseeded =
+            GameState::new_with_rng(players(4), GameConfig::default(), StdRng::seed_from_u64(42))
+                .unwrap();
+        assert!(Uuid::parse_str(unseeded.deck_seed()).is_ok());
+    }
+
+    #[test]
+    fn counts_opponents_who_could_beat_a_play() {
+        let game_state = seat(&["3C 9C", "4C 4S", "5C", "KC KS KH"]);
+        let public_info = game_state.public_info();
+        // only the two opponents with 2 or more cards could hold a pair
+        assert_eq!(
+            public_info.opponents_who_can_beat(play("4C 4S"), &cards("KC KS")),
+            2
+        );
+        assert_eq!(
+            public_info.opponents_who_can_beat(play("4C 4S"), &cards("5C 6C KD")),
+            0
+        );
+        assert_eq!(
+            public_info.opponents_who_can_beat(play("9C"), &cards("KC")),
+            3
+        );
+        assert_eq!(
+            public_info.opponents_who_can_beat(play("KC"), &cards("9C QH")),
+            0
+        );
+    }
+
+    #[test]
+    fn public_info_lists_finishers_in_order() {
+        let mut game_state = seat(&["3C", "4C", "5C 6C", "7C 8C"]);
+        assert!(game_state.public_info().finished.is_empty());
+        play_out(&mut game_state, &["3C", "4C"]);
+        assert_eq!(
+            game_state.public_info().finished,
+            [id_of(&game_state, "p0"), id_of(&game_state, "p1")]
+        );
+    }
+
+    #[test]
+    fn forks_play_on_without_touching_the_original() {
+        let mut game_state =
+            GameState::new_with_seed(players(4), GameConfig::default(), 0).unwrap();
+        game_state.run_pregame();
+        let before = seating(&game_state);
+
+        let mut fork = game_state.fork();
+        assert_eq!(fork.permitted_actions(), game_state.permitted_actions());
+        let branch = fork.clone();
+        while fork.still_playing() {
+            fork.apply(&fork.permitted_actions()[0]);
+        }
+        assert_eq!(fork.standings().len(), 4);
+        assert!(game_state.history.is_empty());
+        assert_eq!(seating(&game_state), before);
+        assert!(branch.history().is_empty());
+        assert_eq!(
+            branch.current_player_id(),
+            game_state.current_player().state.id
+        );
+    }
+
+    #[test]
+    fn the_starting_card_leads() {
+        let suit_order = [Suit::Clubs, Suit::Spades, Suit::Hearts, Suit::Diamonds];
+        for seed in 0..20 {
+            let config = GameConfig {
+                starting_rule: StartingRule::LowestCard,
+                ..GameConfig::default()
+            };
+            let mut game_state = GameState::new_with_seed(players(5), config, seed).unwrap();
+            game_state.run_pregame();
+            let lowest = game_state
+                .table
+                .iter()
+                .flat_map(|player| player.state.current_hand.iter().copied())
+                .min_by_key(|card| {
+                    let suit_idx = suit_order.iter().position(|&suit| suit == card.suit());
+                    (card.value(), suit_idx)
+                })
+                .unwrap();
+            assert!(game_state
+                .current_player()
+                .state
+                .current_hand
+                .contains(&lowest));
+            assert!(game_state
+                .permitted_actions()
+                .iter()
+                .all(|action| action.card_plays()[0].to_vec().contains(&lowest)));
+
+            // the first of the default starting cards that was dealt
+            let mut game_state =
+                GameState::new_with_seed(players(5), GameConfig::default(), seed).unwrap();
+            game_state.run_pregame();
+            let starting_card = cards("3C 3S 3H 3D 4C")
+                .into_iter()
+                .find(|card| !game_state.deck_remaining().contains(card))
+                .unwrap();
+            assert!(game_state
+                .current_player()
+                .state
+                .current_hand
+                .contains(&starting_card));
+        }
+    }
+
+    #[test]
+    fn summary_describes_the_table() {
+        let mut game_state = seat(&["3C 9C", "4C KC"]);
+        play_out(&mut game_state, &["3C"]);
+        let summary = game_state.summary();
+        assert_eq!(
+            summary,
+            GameSummary {
+                num_players: 2,
+                top_card: Some(play("3C")),
+                turns_played: 1,
+                current_player: "p1".to_string(),
+                reversed: false,
+                players: vec![
+                    PlayerSummary {
+                        name: "p1".to_string(),
+                        hand_size: 2,
+                        role: None,
+                    },
+                    PlayerSummary {
+                        name: "p0".to_string(),
+                        hand_size: 1,
+                        role: None,
+                    },
+                ],
+            }
+        );
+        let json = serde_json::to_string(&summary).unwrap();
+        assert_eq!(serde_json::from_str::<GameSummary>(&json).unwrap(), summary);
+    }
+
+    #[test]
+    fn hand_picked_deals_keep_ids_order_and_top_card() {
+        let seated = |hands: &[&str]| {
+            hands
+                .iter()
+                .enumerate()
+                .map(|(idx, hand)| {
+                    (
+                        Uuid::from_u128(idx as u128),
+                        format!("p{idx}"),
+                        cards(hand),
+                        Box::new(FirstAction) as Box<dyn Strategy>,
+                    )
+                })
+                .collect_vec()
+        };
+        let mut game_state =
+            GameState::new_with_hands(seated(&["3C 9C", "4C KC"]), Some(play("8C"))).unwrap();
+        assert_eq!(game_state.current_player().state.id, Uuid::from_u128(0));
+        assert_eq!(
+            game_state.permitted_actions(),
+            [play_cards("9C"), Action::Pass]
+        );
+        // nobody played the preset card, so it clears once everyone passes on it
+        play_out(&mut game_state, &["pass", "pass"]);
+        assert_eq!(game_state.top_card, None);
+
+        assert_eq!(
+            GameState::new_with_hands(seated(&["3C 9C", "9C"]), None).unwrap_err(),
+            GameError::DuplicateCard(cards("9C")[0])
+        );
+        assert_eq!(
+            GameState::new_with_hands(seated(&["3C"]), None).unwrap_err(),
+            GameError::TooFewPlayers(1)
+        );
+        // table sizes a full deck can't be dealt to are fine with hands given directly
+        for num_players in [9, 26] {
+            let one_card_each = Card::all_cards()
+                .into_iter()
+                .take(num_players)
+                .map(|card| {
+                    let strategy = Box::new(FirstAction) as Box<dyn Strategy>;
+                    (Uuid::new_v4(), card.to_string(), vec![card], strategy)
+                })
+                .collect();
+            assert!(GameState::new_with_hands(one_card_each, None).is_ok());
+        }
+    }
+
+    #[test]
+    fn illegal_actions_are_rejected_and_change_nothing() {
+        let mut game_state = seat(&["3C 9C", "4C 4S KC"]);
+        let hand_sizes = |game_state: &GameState| {
+            ["p0", "p1"].map(|name| game_state.hand_size_of(id_of(game_state, name)))
+        };
+
+        assert_eq!(
+            game_state.try_apply_action(&play_cards("9C 9S")),
+            Err(ActionError::NotInHand(cards("9S")[0]))
+        );
+        assert_eq!(
+            game_state.try_apply_action(&Action::SendCard {
+                to: id_of(&game_state, "p1"),
+                card: cards("3C")[0],
+            }),
+            Err(ActionError::SendDuringGame)
+        );
+        assert!(game_state.history.is_empty());
+        assert_eq!(hand_sizes(&game_state), [Some(2), Some(3)]);
+
+        game_state.try_apply_action(&play_cards("9C")).unwrap();
+        assert_eq!(
+            game_state.try_apply_action(&play_cards("4C")),
+            Err(ActionError::NotGreaterThanTop {
+                card_play: play("4C"),
+                top_card: Some(play("9C")),
+            })
+        );
+        let double_down = Action::PlayThenComplete {
+            first: play("4C"),
+            complete: play("4S"),
+        };
+        assert_eq!(
+            game_state.try_apply_action(&double_down),
+            Err(ActionError::InvalidDoubleDown {
+                first: play("4C"),
+                complete: play("4S"),
+            })
+        );
+        assert_eq!(game_state.history.len(), 1);
+        assert_eq!(current_name(&game_state), "p1");
+        assert_eq!(hand_sizes(&game_state), [Some(1), Some(3)]);
+    }
+
+    #[test]
+    fn actions_outside_the_rules_are_not_permitted() {
+        let mut game_state = deal_with_first_hand(4, "3C 3S 3H 3D 9C");
+        game_state.config.allow_breaking_sets = false;
+        game_state.run_pregame();
+        for action in [Action::Pass, play_cards("9C"), play_cards("3C")] {
+            assert_eq!(
+                game_state.try_apply_action(&action),
+                Err(ActionError::NotPermitted(action))
+            );
+        }
+        assert!(game_state.history.is_empty());
+        game_state
+            .try_apply_action(&play_cards("3C 3S 3H 3D"))
+            .unwrap();
+        assert_eq!(
+            game_state.try_apply_action(&Action::Pass),
+            Ok(()),
+            "passing is fine once there's a top card"
+        );
+    }
+
+    #[test]
+    fn sticky_passes_by_everyone_else_clear_the_pile() {
+        let mut game_state = deal_with_first_hand(4, "3C 9C");
+        game_state.config.sticky_passes = true;
+        game_state.run_pregame();
+        assert_eq!(
+            game_state.try_apply_action(&Action::Pass),
+            Err(ActionError::NotPermitted(Action::Pass))
+        );
+        game_state.perform_ingame_action(&play_cards("3C"));
+        for _ in 0..3 {
+            game_state.perform_ingame_action(&Action::Pass);
+        }
+        assert_eq!(game_state.top_card, None);
+        assert!(game_state.public_info().passed_this_round.is_empty());
+        assert_eq!(current_name(&game_state), "p0");
+        assert_eq!(
+            game_state.try_apply_action(&Action::Pass),
+            Err(ActionError::NotPermitted(Action::Pass))
+        );
+    }
+
+    #[test]
+    fn double_down_clears_the_pile_even_below_the_top_card() {
+        let double_down = Action::PlayThenComplete {
+            first: play("5C 5S"),
+            complete: play("5H 5D"),
+        };
+        let hands = ["6C 6S 9C", "5C 5S 5H 5D KC"];
+        let mut game_state = seat(&hands);
+        play_out(&mut game_state, &["6C 6S"]);
+        assert!(!game_state.permitted_actions().contains(&double_down));
+
+        let mut game_state = seat(&hands);
+        game_state.config.double_down_clears = true;
+        play_out(&mut game_state, &["6C 6S"]);
+        assert!(game_state.permitted_actions().contains(&double_down));
+        game_state.perform_ingame_action(&double_down);
+        assert_eq!(game_state.top_card, None);
+        assert_eq!(current_name(&game_state), "p1");
+        assert_eq!(
+            game_state.hand_of(id_of(&game_state, "p1")),
+            Some(&cards("KC")[..])
+        );
+    }
+
+    #[test]
+    fn balanced_deals_still_hold_sets() {
+        let config = GameConfig {
+            deal_mode: DealMode::Balanced,
+            ..GameConfig::default()
+        };
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut largest_sets = Vec::new();
+        for _ in 0..20 {
+            let (hands, _) = deal(&config, 4, &mut rng);
+            for hand in hands {
+                let largest_set = hand
+                    .grouped_by_rank()
+                    .into_iter()
+                    .map(|(_, cards)| cards.len())
+                    .max();
+                largest_sets.push(largest_set.unwrap());
+            }
+        }
+        assert!(
+            largest_sets.iter().all(|&size| size >= 2),
+            "{largest_sets:?}"
+        );
+        assert!(largest_sets.contains(&4), "{largest_sets:?}");
+    }
+
+    #[test]
+    fn balanced_deals_spread_out_the_2s() {
+        let config = GameConfig {
+            deal_mode: DealMode::Balanced,
+            ..GameConfig::default()
+        };
+        let mut rng = StdRng::seed_from_u64(0);
+        for num_players in 2..=8 {
+            for _ in 0..10 {
+                let (hands, _) = deal(&config, num_players, &mut rng);
+                let twos = hands
+                    .iter()
+                    .map(|hand| hand.by_rank(Rank::Two).len())
+                    .collect_vec();
+                assert!(
+                    twos.iter()
+                        .all(|&num_twos| num_twos <= 4usize.div_ceil(num_players)),
+                    "{num_players} players got {twos:?} 2s"
+                );
+                let values = hands
+                    .iter()
+                    .map(|hand| hand.iter().map(Card::value).sum::<usize>())
+                    .collect_vec();
+                let spread = values.iter().max().unwrap() - values.iter().min().unwrap();
+                assert!(
+                    spread <= 13,
+                    "{num_players} players got hand values {values:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn phase_follows_the_game() {
+        let mut game_state = after_one_game();
+        assert_eq!(game_state.current_phase(), Phase::Pregame);
+        assert_eq!(game_state.public_info().phase, Phase::Pregame);
+        game_state.run_pregame();
+        assert_eq!(game_state.current_phase(), Phase::Ingame);
+        while game_state.still_playing() {
+            let action = game_state.permitted_actions()[0];
+            game_state.perform_ingame_action(&action);
+        }
+        assert_eq!(game_state.current_phase(), Phase::Finished);
+
+        // a scripted deal never runs the pregame, so its first play is what starts the game
+        let mut game_state = seat(&["3C 9C", "4C KC"]);
+        assert_eq!(game_state.current_phase(), Phase::Pregame);
+        play_out(&mut game_state, &["3C"]);
+        assert_eq!(game_state.current_phase(), Phase::Ingame);
+    }
+
+    #[test]
+    fn last_place_rule_places_players_still_holding_cards() {
+        let standings_by_rule = |last_place_rule| {
+            let mut game_state = seat(&["3C", "4C 5C", "6C 7C", "8C 9C"]);
+            game_state.config.last_place_rule = last_place_rule;
+            play_out(&mut game_state, &["3C"]);
+            named_standings(&game_state)
+        };
+        let standings = |expected: [(&str, usize, Role); 4]| {
+            expected.map(|(name, place, role)| (name.to_string(), place, role))
+        };
+        // p1 is next to act, so would go out first
+        assert_eq!(
+            standings_by_rule(LastPlaceRule::Ordered),
+            standings([
+                ("p0", 1, Role::President),
+                ("p1", 2, Role::VicePresident),
+                ("p2", 3, Role::ViceAsshole),
+                ("p3", 4, Role::Asshole),
+            ])
+        );
+        assert_eq!(
+            standings_by_rule(LastPlaceRule::Shared),
+            standings([
+                ("p0", 1, Role::President),
+                ("p1", 2, Role::VicePresident),
+                ("p2", 2, Role::ViceAsshole),
+                ("p3", 2, Role::Asshole),
+            ])
+        );
+        assert_eq!(
+            standings_by_rule(LastPlaceRule::SharedAssholes),
+            standings([
+                ("p0", 1, Role::President),
+                ("p1", 2, Role::Asshole),
+                ("p2", 2, Role::Asshole),
+                ("p3", 2, Role::Asshole),
+            ])
+        );
+    }
+
+    #[test]
+    fn looks_up_hands_by_player_id() {
+        let game_state = seat(&["3C 9C", "4C"]);
+        let p0 = id_of(&game_state, "p0");
+        assert_eq!(game_state.hand_of(p0), Some(&cards("3C 9C")[..]));
+        assert_eq!(game_state.hand_size_of(p0), Some(2));
+        assert_eq!(game_state.fork().hand_of(p0), Some(&cards("3C 9C")[..]));
+        assert_eq!(game_state.hand_of(Uuid::nil()), None);
+        assert_eq!(game_state.hand_size_of(Uuid::nil()), None);
+    }
+
+    #[test]
+    fn checks_legality_without_playing() {
+        let mut game_state = deal_with_first_hand(4, "3C 3S 9C");
+        game_state.run_pregame();
+        // the first play has to include the starting card
+        assert!(game_state.is_action_legal(&play_cards("3C")));
+        assert!(game_state.is_action_legal(&play_cards("3C 3S")));
+        assert!(!game_state.is_action_legal(&play_cards("9C")));
+        assert!(!game_state.is_action_legal(&Action::Pass));
+        assert!(game_state.fork().is_action_legal(&play_cards("3C")));
+
+        game_state.perform_ingame_action(&play_cards("3C 3S"));
+        assert!(game_state.is_action_legal(&Action::Pass));
+        let next_hand = game_state.current_player().state.current_hand.clone();
+        assert!(!game_state.is_action_legal(&Action::PlayCards {
+            card_play: CardPlay::Single(next_hand[0])
+        }));
+        assert_eq!(game_state.history.len(), 1);
+    }
+
+    #[test]
+    fn scripted_decks_deal_in_order() {
+        let deck = Card::all_cards();
+        let game_state = GameState::new_with_deck(players(5), deck.clone()).unwrap();
+        for (player, hand) in game_state.table.iter().zip(deck.chunks(10)) {
+            assert_eq!(player.state.current_hand, hand);
+        }
+        assert_eq!(game_state.deck_remaining(), &deck[50..]);
+
+        assert_eq!(
+            GameState::new_with_deck(players(4), deck[1..].to_vec()).unwrap_err(),
+            GameError::WrongDeckSize(51)
+        );
+        let mut duplicated = deck.clone();
+        duplicated[1] = deck[0];
+        assert_eq!(
+            GameState::new_with_deck(players(4), duplicated).unwrap_err(),
+            GameError::DuplicateCard(deck[0])
+        );
+    }
+
+    #[test]
+    fn played_cards_and_my_hand_are_out_of_play() {
+        let mut game_state = seat(&["3C 9C", "4C 4S KC", "5C 6C"]);
+        play_out(&mut game_state, &["3C", "4C", "pass"]);
+        let my_hand = cards("9C");
+        assert_eq!(
+            game_state.public_info().definitely_out_of_play(&my_hand),
+            cards("3C 4C 9C")
+        );
+    }
+
+    #[test]
+    fn double_down_is_never_the_first_play() {
+        let mut game_state = deal_with_first_hand(2, "3C 5C 5S 5H 5D");
+        game_state.config.double_down_clears = true;
+        game_state.run_pregame();
+        game_state.top_card = Some(play("4C 4S"));
+        let double_down = Action::PlayThenComplete {
+            first: play("5C 5S"),
+            complete: play("5H 5D"),
+        };
+        assert!(!game_state.is_action_legal(&double_down));
+        assert_eq!(
+            game_state.try_apply_action(&double_down),
+            Err(ActionError::InvalidDoubleDown {
+                first: play("5C 5S"),
+                complete: play("5H 5D"),
+            })
+        );
+    }
+}
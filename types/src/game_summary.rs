@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{CardPlay, Role};
+
+/// Snapshot of a game for dashboards and other programs, made by `GameState::summary`
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameSummary {
+    pub num_players: usize,
+    pub top_card: Option<CardPlay>,
+    /// Ingame actions taken so far, passes included
+    pub turns_played: usize,
+    pub current_player: String,
+    pub reversed: bool,
+    /// In seating order, starting with the current player
+    pub players: Vec<PlayerSummary>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlayerSummary {
+    pub name: String,
+    pub hand_size: usize,
+    pub role: Option<Role>,
+}
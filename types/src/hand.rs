@@ -1,3 +1,4 @@
+use deckofcards::Rank;
 use itertools::Itertools;
 
 use crate::{Card, CardPlay};
@@ -8,13 +9,16 @@ pub trait Hand {
     fn pairs(&self) -> Vec<CardPlay>;
     fn triples(&self) -> Vec<CardPlay>;
     fn quads(&self) -> Vec<CardPlay>;
+    fn by_rank(&self, rank: Rank) -> Vec<Card>;
+    /// Cards grouped by rank, lowest to highest in game order, so 2s come last
+    fn grouped_by_rank(&self) -> Vec<(Rank, Vec<Card>)>;
 }
 
 fn _card_plays_for_size(hand: &[Card], card_play_size: usize) -> Vec<CardPlay> {
     hand.iter()
         .combinations(card_play_size)
         .filter_map(|cards| {
-            let rank = cards.get(0)?.rank();
+            let rank = cards.first()?.rank();
             if cards.iter().all(|c| c.rank() == rank) {
                 Some(CardPlay::from_cards(&cards))
             } else {
@@ -49,4 +53,47 @@ impl Hand for Vec<Card> {
     fn quads(&self) -> Vec<CardPlay> {
         _card_plays_for_size(self, 4)
     }
+
+    fn by_rank(&self, rank: Rank) -> Vec<Card> {
+        self.iter()
+            .filter(|card| card.rank() == rank)
+            .copied()
+            .collect()
+    }
+
+    fn grouped_by_rank(&self) -> Vec<(Rank, Vec<Card>)> {
+        self.iter()
+            .copied()
+            .sorted()
+            .group_by(|card| card.rank())
+            .into_iter()
+            .map(|(rank, cards)| (rank, cards.collect()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use deckofcards::Suit;
+
+    use super::*;
+
+    #[test]
+    fn groups_cards_by_rank_in_game_order() {
+        let two = Card::new(Rank::Two, Suit::Hearts);
+        let three = Card::new(Rank::Three, Suit::Clubs);
+        let king_clubs = Card::new(Rank::King, Suit::Clubs);
+        let king_spades = Card::new(Rank::King, Suit::Spades);
+        let hand = vec![king_spades, two, three, king_clubs];
+
+        assert_eq!(hand.by_rank(Rank::King).len(), 2);
+        assert!(hand.by_rank(Rank::Ace).is_empty());
+
+        let groups = hand.grouped_by_rank();
+        let ranks = groups.iter().map(|(rank, _)| *rank).collect_vec();
+        assert_eq!(ranks, [Rank::Three, Rank::King, Rank::Two]);
+        let kings = &groups[1].1;
+        assert_eq!(kings.len(), 2);
+        assert!(kings.contains(&king_clubs) && kings.contains(&king_spades));
+    }
 }
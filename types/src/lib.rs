@@ -1,14 +1,22 @@
 pub mod action;
 pub mod card;
 pub mod card_play;
+pub mod game_config;
 pub mod game_state;
+pub mod game_summary;
 pub mod hand;
 pub mod player;
 pub mod player_state;
+pub mod player_view;
+pub mod sim_game_state;
 
 pub use action::Action;
 pub use card::Card;
-pub use card_play::CardPlay;
-pub use game_state::{Event, GameState};
+pub use card_play::{CardPlay, CardPlayError};
+pub use game_config::{DealMode, GameConfig, LastPlaceRule, StartingRule};
+pub use game_state::{ActionError, Event, GameError, GameState, Phase, ReplayError};
+pub use game_summary::{GameSummary, PlayerSummary};
 pub use player::{Player, Strategy};
 pub use player_state::{PlayerState, PublicPlayerState, Role};
+pub use player_view::PlayerView;
+pub use sim_game_state::SimGameState;
@@ -3,12 +3,20 @@ use std::fmt::Debug;
 use crate::{game_state::PublicInfo, Action, PlayerState};
 
 pub trait Strategy: Debug {
+    /// Picks one of `available_actions`. In pregame these are `SendCard`s, one call per card,
+    /// and `private_info.current_hand` excludes cards already chosen to send, so a strategy
+    /// can look at the rest of its hand to keep sets together.
     fn select_action(
         &mut self,
         private_info: &PlayerState,
         public_info: &PublicInfo,
         available_actions: &[Action],
     ) -> Action;
+
+    /// Short name for logs, e.g. the name it's registered under
+    fn name(&self) -> &'static str {
+        "unknown"
+    }
 }
 
 #[derive(Debug)]
@@ -1,11 +1,16 @@
-use std::fmt::{Debug, Display};
+use std::{
+    collections::HashMap,
+    fmt::{Debug, Display},
+};
 
+use deckofcards::Rank;
 use itertools::Itertools;
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::card::Card;
+use crate::{card::Card, card_play::CardPlay, hand::Hand};
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Role {
     President,
     VicePresident,
@@ -26,7 +31,7 @@ impl Display for Role {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct PlayerState {
     pub id: Uuid,
     pub name: String,
@@ -34,7 +39,7 @@ pub struct PlayerState {
     pub current_hand: Vec<Card>,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct PublicPlayerState {
     pub id: Uuid,
     pub name: String,
@@ -82,6 +87,33 @@ impl PlayerState {
         }
     }
 
+    pub fn is_finished(&self) -> bool {
+        self.current_hand.is_empty()
+    }
+
+    /// Removes every card of `card_play` from the hand, or none of them if any is missing, in
+    /// which case the first missing card is returned
+    pub fn remove_card_play(&mut self, card_play: &CardPlay) -> Result<(), Card> {
+        let mut remaining = self.current_hand.clone();
+        for card in card_play.to_vec() {
+            if !remaining.remove_card(&card) {
+                return Err(card);
+            }
+        }
+        self.current_hand = remaining;
+        Ok(())
+    }
+
+    /// Sum of `Card::value` over the hand, so 2s count highest
+    pub fn hand_value(&self) -> usize {
+        self.current_hand.iter().map(|card| card.value()).sum()
+    }
+
+    /// Number of cards held of each rank
+    pub fn shape(&self) -> HashMap<Rank, usize> {
+        self.current_hand.iter().counts_by(|card| card.rank())
+    }
+
     pub fn top_k_cards(&self, num_cards: usize) -> Vec<Card> {
         self.current_hand
             .iter()
@@ -101,3 +133,53 @@ impl PlayerState {
             .collect()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use deckofcards::Suit;
+
+    use super::*;
+
+    fn card(rank: Rank, suit: Suit) -> Card {
+        Card::new(rank, suit)
+    }
+
+    #[test]
+    fn remove_card_play_takes_all_or_nothing() {
+        let five_clubs = card(Rank::Five, Suit::Clubs);
+        let five_spades = card(Rank::Five, Suit::Spades);
+        let five_hearts = card(Rank::Five, Suit::Hearts);
+        let king = card(Rank::King, Suit::Clubs);
+        let mut state =
+            PlayerState::new("p0".to_string(), vec![five_clubs, king, five_spades], None);
+
+        let missing_one = CardPlay::Pair(five_clubs, five_hearts);
+        assert_eq!(state.remove_card_play(&missing_one), Err(five_hearts));
+        assert_eq!(state.current_hand, [five_clubs, king, five_spades]);
+
+        let pair = CardPlay::Pair(five_clubs, five_spades);
+        assert_eq!(state.remove_card_play(&pair), Ok(()));
+        assert_eq!(state.current_hand, [king]);
+        assert_eq!(state.remove_card_play(&pair), Err(five_clubs));
+    }
+
+    #[test]
+    fn summarizes_hand_value_and_shape() {
+        let hand = vec![
+            card(Rank::Three, Suit::Clubs),
+            card(Rank::Three, Suit::Hearts),
+            card(Rank::Two, Suit::Spades),
+        ];
+        let state = PlayerState::new("p0".to_string(), hand, None);
+        // 3s are worth 1 each and 2s, above aces, 13
+        assert_eq!(state.hand_value(), 15);
+        assert_eq!(
+            state.shape(),
+            HashMap::from([(Rank::Three, 2), (Rank::Two, 1)])
+        );
+        assert_eq!(
+            PlayerState::new("p1".to_string(), vec![], None).hand_value(),
+            0
+        );
+    }
+}
@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{game_state::PublicInfo, PlayerState};
+
+/// One player's perspective on a game, made by `GameState::view_for`: their own hand, and only
+/// hand sizes for everyone else
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PlayerView {
+    pub player: PlayerState,
+    pub public_info: PublicInfo,
+}
@@ -0,0 +1,86 @@
+use uuid::Uuid;
+
+use crate::{
+    game_state::{Event, PublicInfo},
+    Action, Card, CardPlay, GameState, PlayerState, Role, Strategy,
+};
+
+// stands in for the real strategies in a fork, which only advances through `apply`
+#[derive(Debug)]
+pub(crate) struct RolloutStrategy;
+
+impl Strategy for RolloutStrategy {
+    fn select_action(
+        &mut self,
+        _private_info: &PlayerState,
+        _public_info: &PublicInfo,
+        _available_actions: &[Action],
+    ) -> Action {
+        panic!("Forked games are advanced with apply and never ask a strategy to choose")
+    }
+}
+
+/// A `GameState` without strategies, made by `GameState::fork`, which can be cloned freely
+/// and advanced by hand, e.g. for lookahead or Monte Carlo rollouts. It follows the same rules
+/// as the game it was forked from.
+#[derive(Debug)]
+pub struct SimGameState {
+    game_state: GameState,
+}
+
+impl Clone for SimGameState {
+    fn clone(&self) -> Self {
+        Self::new(
+            self.game_state
+                .copy_with_strategies(|| Box::new(RolloutStrategy)),
+        )
+    }
+}
+
+impl SimGameState {
+    pub(crate) fn new(game_state: GameState) -> Self {
+        Self { game_state }
+    }
+
+    pub fn permitted_actions(&self) -> Vec<Action> {
+        self.game_state.permitted_actions()
+    }
+
+    pub fn is_action_legal(&self, action: &Action) -> bool {
+        self.game_state.is_action_legal(action)
+    }
+
+    /// Plays `action` for the current player; panics like `perform_ingame_action` if it isn't
+    /// legal
+    pub fn apply(&mut self, action: &Action) {
+        self.game_state.perform_ingame_action(action);
+    }
+
+    pub fn still_playing(&self) -> bool {
+        self.game_state.still_playing()
+    }
+
+    pub fn current_player_id(&self) -> Uuid {
+        self.game_state.current_player().state.id
+    }
+
+    pub fn hand_of(&self, player_id: Uuid) -> Option<&[Card]> {
+        self.game_state.hand_of(player_id)
+    }
+
+    pub fn top_card(&self) -> Option<CardPlay> {
+        self.game_state.top_card
+    }
+
+    pub fn history(&self) -> &[Event] {
+        &self.game_state.history
+    }
+
+    pub fn public_info(&self) -> PublicInfo {
+        self.game_state.public_info()
+    }
+
+    pub fn standings(&self) -> Vec<(Uuid, usize, Role)> {
+        self.game_state.standings()
+    }
+}